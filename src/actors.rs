@@ -1,6 +1,7 @@
 
 pub(crate) use ntangler::Ntangler;
 pub(crate) use llmclient::LlmClient;
+pub(crate) use repositories::GitRepository;
 
 mod repositories;
 mod scribe;
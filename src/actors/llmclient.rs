@@ -1,71 +1,59 @@
-use std::env;
+use std::collections::HashMap;
 use std::fmt::Debug;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
 use akton::prelude::*;
-use async_openai::config::OpenAIConfig;
-use async_openai::error::OpenAIError;
-use async_openai::types::{AssistantEventStream, AssistantsApiResponseFormat, AssistantsApiResponseFormatOption, AssistantStreamEvent, CreateMessageRequest, CreateMessageRequestContent, CreateRunRequest, CreateThreadRequest, MessageDeltaContent, MessageRole, ThreadObject};
-use async_openai::types::AssistantsApiResponseFormatOption::Format;
-use async_openai::types::AssistantsApiResponseFormatType::JsonObject;
-use failsafe::Config;
-use failsafe::futures::CircuitBreaker;
-use futures::StreamExt;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc::Sender;
-use tokio::time::timeout;
-use tracing::{debug, error, info, instrument, trace, warn};
+use tracing::{error, info, instrument, warn};
 
-use crate::messages::{CommitMessageGenerated, DiffQueued, GenerationStarted};
-use crate::models::CommitMessage;
+use crate::messages::{CommitMessageGenerated, DiffQueued, GenerationFailed};
+use crate::models::{CommitMessage, GenerationMetadata, NtangledRepository};
+use crate::models::config::{ProviderConfig, RetryConfig};
+
+use providers::{CommitRequest, Provider, Retried};
+use redaction::Redactor;
+use template::PromptTemplate;
+
+mod providers;
+mod redaction;
+mod template;
 
 #[derive(Clone, Debug)]
 pub struct LlmClient {
-    client: Client,
-    endpoint: String,
-    api_key: Option<String>,
+    /// Used for repositories without a provider of their own.
+    provider: Arc<dyn Provider>,
+    /// Providers by repository nickname, in the order they're tried.
+    providers: HashMap<String, Vec<Arc<dyn Provider>>>,
+    /// Redaction rules by repository nickname.
+    redactors: HashMap<String, Redactor>,
+    /// Prompt templates by repository nickname.
+    templates: HashMap<String, PromptTemplate>,
 }
 
 impl Default for LlmClient {
     fn default() -> Self {
         LlmClient {
-            client: Client::new(),
-            endpoint: String::default(),
-            api_key: None,
+            provider: providers::from_config(&ProviderConfig::default(), &RetryConfig::default(), false).expect("Failed to create default provider"),
+            providers: HashMap::new(),
+            redactors: HashMap::new(),
+            templates: HashMap::new(),
         }
     }
 }
 
 impl LlmClient {
-    #[instrument(skip(system, config))]
-    pub async fn initialize(config: ActorConfig, system: &mut AktonReady) -> anyhow::Result<Context> {
+    #[instrument(skip(system, config, repositories))]
+    pub async fn initialize(config: ActorConfig, system: &mut AktonReady, repositories: &[NtangledRepository]) -> anyhow::Result<Context> {
         let mut actor = system.create_actor_with_config::<LlmClient>(config).await;
-
-        // Initialize with default values, these will be set properly later
-        actor.state.client = Client::new();
-        // Read endpoint from environment variable
-        actor.state.endpoint = env::var("NTANGLER_ENDPOINT")
-            .unwrap_or_else(|_| {
-                warn!("NTANGLER_ENDPOINT not set, using default endpoint");
-                "https://api.openai.com/v1".to_string() // Default OpenAI endpoint
-            });
-
-        // Read API key from environment variable
-        actor.state.api_key = env::var("NTANGLER_API_TOKEN").ok();
-
-        if actor.state.api_key.is_none() {
-            warn!("NTANGLER_API_TOKEN not set, API calls may fail");
-        }
+        actor.state = LlmClient::for_repositories(repositories)?;
 
         actor.setup.act_on_async::<DiffQueued>(|actor, event| {
             let return_address = event.message.reply_address.clone();
             let message = event.message.clone();
-            let client = actor.state.client.clone();
-            let api_key = actor.state.api_key.clone();
-            let endpoint = actor.state.endpoint.clone();
+            let client = actor.state.clone();
+            let broker = actor.akton.get_broker().clone();
             info!("Received DiffQueued event: {:?}", event);
-            Context::wrap_future(Self::handle_generate_commit_message(message, return_address, client, api_key, endpoint) )
+            Context::wrap_future(Self::handle_generate_commit_message(message, return_address, broker, client) )
         });
 
         actor.context.subscribe::<DiffQueued>().await;
@@ -73,260 +61,235 @@ impl LlmClient {
         Ok(actor.activate(None).await)
     }
 
-    #[instrument(skip(message, return_address, client, api_key))]
-    async fn handle_generate_commit_message(message: DiffQueued, return_address: Context, client: Client, api_key: Option<String>, endpoint: String) {
+    #[instrument(skip(message, return_address, broker, client))]
+    async fn handle_generate_commit_message(message: DiffQueued, return_address: Context, broker: Context, client: LlmClient) {
         let return_address = return_address.clone();
-        match Self::generate_commit_message(client, endpoint, api_key, message.diff).await {
-            Ok(commit_message) => {
+        match client.generate(&message.repository_nickname, Some(&message.target_file), message.diff, Vec::new(), message.examples).await {
+            Ok((commit_message, metadata)) => {
                 return_address.emit_async(
-                    CommitMessageGenerated { target_file: message.target_file.clone(), commit_message },
+                    CommitMessageGenerated { target_file: message.target_file.clone(), commit_message, metadata, amending: message.amending },
                     None,
                 ).await
             }
             Err(e) => {
-                error!("{e}");
+                error!(repo = message.repository_nickname, file = ?message.target_file, "{e:#}");
+                let generation_failed = GenerationFailed::new(
+                    message.target_file,
+                    message.repository_nickname,
+                    providers::failure_kind(&e),
+                    e.downcast_ref::<Retried>().map_or(0, |retried| retried.retries),
+                    e.root_cause().to_string(),
+                );
+                broker.emit_async(BrokerRequest::new(generation_failed), None).await;
             }
         }
     }
 
-    pub fn configure(&mut self, endpoint: String, api_key: Option<String>) {
-        self.endpoint = endpoint;
-        self.api_key = api_key;
-    }
-
-    #[instrument(skip(client))]
-    async fn generate_commit_message(client: Client, endpoint: String, api_key: Option<String>, diff: String) -> anyhow::Result<CommitMessage> {
-        let request = CommitRequest { diff };
-        let mut req_builder = client.post(&endpoint)
-            .header("Content-Type", "application/json");
-
-        if let Some(key) = &api_key {
-            req_builder = req_builder.header("Authorization", format!("Bearer {}", key));
-        }
-
-        let response = req_builder
-            .json(&request)
-            .send()
-            .await?;
-
-        // Log response details
-        debug!("Response status: {}", response.status());
-        debug!("Response headers:");
-        for (name, value) in response.headers().iter() {
-            debug!("  {}: {}", name, value.to_str().unwrap_or("<non-string value>"));
-        }
-
-        // Handle streaming response
-        let mut full_body = String::new();
-        let mut stream = response.bytes_stream();
-
-        while let Some(item) = stream.next().await {
-            let chunk = item?;
-            full_body.push_str(&String::from_utf8_lossy(&chunk));
-            debug!("Received chunk: {}", String::from_utf8_lossy(&chunk));
-        }
-
-        debug!("Full response body: {}", full_body);
-
-        // Parse the full response body
-        let commit_message: CommitMessage = serde_json::from_str(&full_body)?;
-
-        Ok(commit_message)
-    }
-    #[instrument]
-    async fn create_run_stream_with_circuit_breaker(
-        circuit_breaker: &(impl CircuitBreaker + Debug),
-        client: &async_openai::Client<OpenAIConfig>,
-        conversation_thread_id: &str,
-        format: Option<AssistantsApiResponseFormatOption>,
-    ) -> anyhow::Result<AssistantEventStream> {
-        match circuit_breaker.call(timeout(Duration::from_secs(10), client.threads().runs(conversation_thread_id).create_stream(CreateRunRequest {
-            assistant_id: "asst_xiaBOCpksCenAMJSL2F0qqFL".to_string(),
-            stream: Some(true),
-            parallel_tool_calls: Some(true),
-            response_format: format,
-            ..Default::default()
-        }))).await {
-            Ok(result) => match result {
-                Ok(stream) => {
-                    info!("Run stream successfully created for conversation_thread_id: {}", conversation_thread_id);
-                    Ok(stream)
-                }
-                Err(e) => {
-                    error!("Timeout while creating run stream for conversation_thread_id: {}", conversation_thread_id);
-                    Err(anyhow::Error::from(e).into())
-                }
-            },
-            Err(_) => {
-                error!("Timeout while creating run stream");
-                Err(anyhow::Error::msg("Timeout while creating run stream"))
+    /// Builds a client that sends each repository's diffs to its configured providers, after
+    /// applying its redaction rules and rendering its prompt template.
+    pub(crate) fn for_repositories(repositories: &[NtangledRepository]) -> anyhow::Result<LlmClient> {
+        let mut client = LlmClient::default();
+        for repository in repositories {
+            let chain = std::iter::once(&repository.provider)
+                .chain(&repository.fallbacks)
+                .enumerate()
+                .map(|(position, provider)| {
+                    providers::from_config(provider, &repository.retry, position < repository.fallbacks.len())
+                        .map_err(|e| e.context(format!("Invalid provider configuration for {}", repository.nickname)))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            client.providers.insert(repository.nickname.clone(), chain);
+            if let Some(template) = PromptTemplate::load(repository)? {
+                client.templates.insert(repository.nickname.clone(), template);
             }
+            if repository.redaction.is_empty() {
+                continue;
+            }
+            let redactor = Redactor::new(&repository.redaction, &repository.path)?;
+            client.redactors.insert(repository.nickname.clone(), redactor);
         }
+        Ok(client)
     }
 
-    #[instrument]
-    async fn create_message_with_circuit_breaker(
-        circuit_breaker: &(impl CircuitBreaker + Debug),
-        client: &async_openai::Client<OpenAIConfig>,
-        conversation_thread_id: &str,
-        diff: String,
-    ) -> anyhow::Result<()> {
-        match circuit_breaker.call(timeout(Duration::from_secs(10), client.threads().messages(conversation_thread_id).create(CreateMessageRequest {
-            role: MessageRole::User,
-            content: CreateMessageRequestContent::from(diff),
-            ..Default::default()
-        }))).await {
-            Ok(result) => match result {
-                Ok(_) => {
-                    info!("Message successfully created in conversation_thread_id: {}", conversation_thread_id);
-                    Ok(())
-                }
+    /// Generates a message for a repository's `diff` to `target_file`, passing along earlier
+    /// commit messages the new one should summarize and examples of the project's style, all
+    /// redacted alike.
+    ///
+    /// Each of the repository's providers is tried in turn until one writes a message. If none
+    /// does, the last error that may pass is returned, so the file is tried again later, or else
+    /// the last provider's error.
+    pub(crate) async fn generate(&self, repository_nickname: &str, target_file: Option<&Path>, diff: String, messages: Vec<String>, examples: Vec<String>) -> anyhow::Result<(CommitMessage, GenerationMetadata)> {
+        let mut redactions = 0;
+        let mut redact = |text: String| {
+            let (text, count) = self.redact(repository_nickname, text);
+            redactions += count;
+            text
+        };
+        let diff = redact(diff);
+        let messages: Vec<String> = messages.into_iter().map(&mut redact).collect();
+        let examples: Vec<String> = examples.into_iter().map(&mut redact).collect();
+        let chain = self.providers.get(repository_nickname).map_or(std::slice::from_ref(&self.provider), Vec::as_slice);
+        let diff_bytes = diff.len();
+        let prompt = self.templates.get(repository_nickname).map(|template| template.render(target_file, &diff, &mut redact));
+        let request = CommitRequest { diff, messages, examples, prompt };
+
+        let mut errors: Vec<anyhow::Error> = Vec::new();
+        for (position, provider) in chain.iter().enumerate() {
+            if let Some(e) = errors.last() {
+                warn!(repo = repository_nickname, provider = provider.name(), "Falling back after: {e:#}");
+            }
+            let started = Instant::now();
+            let response = match provider.generate(&request).await {
+                Ok(response) => response,
                 Err(e) => {
-                    error!("Failed to create message in conversation_thread_id {}: {:?}", conversation_thread_id, e);
-                    Err(anyhow::Error::from(e).into())
+                    errors.push(e);
+                    continue;
                 }
-            },
-            Err(e) => {
-                error!("Circuit breaker call failed while creating message in thread_id {}: {:?}", conversation_thread_id, e);
-                Err(anyhow::Error::from(e))
-            }
+            };
+            info!(repo = repository_nickname, provider = provider.name(), endpoint = provider.endpoint(), "Generated commit message");
+
+            let metadata = GenerationMetadata {
+                provider: provider.name().to_string(),
+                fallback: position > 0,
+                endpoint: provider.endpoint().to_string(),
+                model: response.model,
+                latency: started.elapsed(),
+                diff_bytes,
+                prompt_hash: response.prompt_hash,
+                redactions,
+                heuristic: provider.heuristic(),
+                ..Default::default()
+            };
+            return Ok((response.commit_message, metadata));
+        }
+        match errors.iter().rposition(|e| providers::failure_kind(e).is_transient()) {
+            Some(position) => Err(errors.swap_remove(position)),
+            None => Err(errors.pop().unwrap_or_else(|| anyhow::anyhow!("No provider is configured for {repository_nickname}"))),
         }
     }
 
-    #[instrument]
-    async fn create_thread_with_circuit_breaker(circuit_breaker: &(impl CircuitBreaker + Debug), client: &async_openai::Client<OpenAIConfig>) -> anyhow::Result<ThreadObject> {
-        match circuit_breaker.call(timeout(Duration::from_secs(10), client.threads().create(CreateThreadRequest::default()))).await {
-            Ok(result) => match result {
-                Ok(conversation_thread) => {
-                    info!("Thread successfully created with id: {}", conversation_thread.id);
-                    Ok(conversation_thread)
-                }
-                Err(e) => {
-                    error!("Failed to create thread: {:?}", e);
-                    Err(anyhow::Error::from(e).into())
-                }
-            },
-            Err(e) => {
-                error!("Circuit breaker call failed while creating thread: {:?}", e);
-                Err(anyhow::Error::from(e))
-            }
+    /// Applies the repository's redaction rules to `text` and audits what they removed,
+    /// returning the text to send and how many redactions were made.
+    fn redact(&self, repository_nickname: &str, text: String) -> (String, usize) {
+        let Some(redactor) = self.redactors.get(repository_nickname) else {
+            return (text, 0);
+        };
+        let (text, redactions) = redactor.redact(&text);
+        if let Err(e) = redactor.audit(&redactions) {
+            warn!(repo = repository_nickname, "Failed to record redactions: {e}");
         }
+        let count = redactions.iter().map(|redaction| redaction.count).sum();
+        (text, count)
     }
-    #[instrument(skip(broker, tx, client))]
-    async fn call_ai_endpoint(broker: Context, tx: Sender<String>, diff: String, repository_nickname: String, target_file_clone: PathBuf, client: async_openai::Client<OpenAIConfig>) {
-        let target_file_clone = target_file_clone.clone();
-        let target_file_display = &target_file_clone.display().to_string();
-        let msg = BrokerRequest::new(GenerationStarted::new(
-            target_file_clone.clone(),
-            repository_nickname.clone(),
-        ));
-        info!("AI endpoint called for repository: {}, file: {}", repository_nickname, target_file_clone.display());
-        broker.emit_async(msg, None).await;
-
-        let circuit_breaker = Config::new().build();
+}
 
-        let client = client.clone();
-        let thread = match Self::create_thread_with_circuit_breaker(&circuit_breaker, &client).await {
-            Ok(thread) => thread,
-            Err(e) => {
-                // TODO: impl fallback logic
-                error!("Error creating thread with circuit breaker for repository: {}, file: {}: {:?}", repository_nickname, target_file_display, e);
-                return; // Fail gracefully by returning early
-            }
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use providers::mock_server::{self, MockResponse};
+
+    use crate::models::config::{RedactionConfig, RedactionPattern};
+    use crate::models::FailureKind;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_generate_falls_back_to_next_provider() {
+        let (primary, _primary_server) = mock_server::serve_once(200, "not a commit message").await;
+        let (fallback, fallback_server) = mock_server::serve_once(
+            200,
+            r#"{"type": "docs", "scope": "", "description": "explain setup", "body": "", "breaking": false}"#,
+        )
+        .await;
+        let repository = NtangledRepository {
+            nickname: "tools".to_string(),
+            provider: ProviderConfig::Ntangler { endpoint: Some(primary) },
+            fallbacks: vec![ProviderConfig::Ntangler { endpoint: Some(fallback.clone()) }],
+            ..Default::default()
         };
+        let client = LlmClient::for_repositories(&[repository]).unwrap();
 
-        let thread_id = thread.id.clone();
-        trace!("Got thread id {} for repository: {}, file: {}", thread_id, repository_nickname, target_file_clone.display());
-        match Self::create_message_with_circuit_breaker(&circuit_breaker, &client, &thread.id, diff).await {
-            Ok(message) => {
-                trace!("Message created successfully in thread id {} for repository: {}, file: {}", thread_id, repository_nickname, target_file_display);
-                message
-            }
-            Err(e) => {
-                // TODO: impl fallback logic
-                error!("Error creating message with circuit breaker for thread id {} in repository: {}, file: {}: {:?}", thread_id, repository_nickname, target_file_display, e);
-                return; // Fail gracefully by returning early
-            }
-        };
+        let (commit_message, metadata) = client.generate("tools", None, "+# Setup\n".to_string(), Vec::new(), Vec::new()).await.unwrap();
 
-        let format = AssistantsApiResponseFormat { r#type: JsonObject };
+        fallback_server.await.unwrap();
+        assert_eq!(commit_message.description.to_string(), "explain setup");
+        assert_eq!(metadata.provider, "ntangler");
+        assert_eq!(metadata.endpoint, fallback);
+        assert!(metadata.fallback);
+    }
 
-        // Step 3: Initiate a run and handle the event stream.
-        let mut event_stream = match Self::create_run_stream_with_circuit_breaker(&circuit_breaker, &client, &thread.id, Some(Format(format))).await {
-            Ok(event_stream) => event_stream,
-            Err(e) => {
-                // TODO: impl fallback logic
-                error!("Error creating run stream with circuit breaker for thread id {} in repository: {}, file: {}: {:?}", thread_id, repository_nickname, target_file_display, e);
-                return; // Fail gracefully by returning early
-            }
+    #[tokio::test]
+    async fn test_generate_reports_a_timeout_before_a_later_rejection() {
+        let (primary, primary_server) = mock_server::serve(vec![MockResponse::new(200, "{}").delay(Duration::from_secs(3))]).await;
+        let (fallback, fallback_server) = mock_server::serve_once(200, "not a commit message").await;
+        let repository = NtangledRepository {
+            nickname: "tools".to_string(),
+            provider: ProviderConfig::Ntangler { endpoint: Some(primary) },
+            fallbacks: vec![ProviderConfig::Ntangler { endpoint: Some(fallback) }],
+            retry: RetryConfig { timeout: 1, backoff: 1, ..Default::default() },
+            ..Default::default()
         };
+        let client = LlmClient::for_repositories(&[repository]).unwrap();
 
-        let mut commit_message = String::new();
-        trace!("Processing events from the event stream for thread id {} in repository: {}, file: {}", thread_id, repository_nickname, target_file_display);
+        let error = client.generate("tools", None, "+# Setup\n".to_string(), Vec::new(), Vec::new()).await.unwrap_err();
 
-        // Processing events from the event stream.
-        while let Some(event) = event_stream.next().await {
-            match event {
-                Ok(event) => match event {
-                    AssistantStreamEvent::ThreadMessageDelta(message) => {
-                        if let Some(content) = message.delta.content {
-                            for item in content {
-                                match item {
-                                    MessageDeltaContent::ImageFile(_)
-                                    | MessageDeltaContent::ImageUrl(_) => {}
-                                    MessageDeltaContent::Text(text) => {
-                                        if let Some(text) = text.text {
-                                            if let Some(text) = text.value {
-                                                commit_message.push_str(&text);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    AssistantStreamEvent::Done(_) => {
-                        trace!("Event stream completed for thread id {} in repository: {}, file: {}", thread_id, repository_nickname, target_file_display);
-                    }
-                    _ => {
-                        warn!("Unhandled event type in the stream for thread id {} in repository: {}, file: {}", thread_id, repository_nickname, target_file_display);
-                    }
-                },
-                Err(e) => {
-                    // Event: Error in Event Stream
-                    // Description: An error occurred while processing the event stream.
-                    // Context: Error details.
-                    match e {
-                        OpenAIError::Reqwest(s) => {
-                            error!("Reqwest error in event stream for thread id {} in repository: {}, file: {}: {}", thread_id, repository_nickname, target_file_display, s);
-                        }
-                        OpenAIError::ApiError(_) => {}
-                        OpenAIError::JSONDeserialize(_) => {}
-                        OpenAIError::FileSaveError(_) => {}
-                        OpenAIError::FileReadError(_) => {}
-                        OpenAIError::StreamError(s) => {
-                            error!("Stream error in event stream for thread id {} in repository: {}, file: {}: {}", thread_id, repository_nickname, target_file_display, s);
-                        }
-                        OpenAIError::InvalidArgument(_) => {}
-                    }
-                }
-            }
-        }
-
-        trace!("Returning commit message for repository: {}, file: {}", repository_nickname, target_file_display);
-        if let Err(e) = tx.send(commit_message).await {
-            error!("Failed to send commit message for repository: {}, file: {}: {}", repository_nickname, target_file_display, e);
-        }
+        fallback_server.await.unwrap();
+        assert_eq!(providers::failure_kind(&error), FailureKind::Timeout);
+        assert!(!error.is::<Retried>());
+        primary_server.abort();
     }
 
-}
+    #[test]
+    fn test_missing_api_key_is_a_configuration_error() {
+        let repository = NtangledRepository {
+            nickname: "tools".to_string(),
+            provider: ProviderConfig::Heuristic,
+            fallbacks: vec![ProviderConfig::Anthropic {
+                endpoint: "http://127.0.0.1:9".to_string(),
+                model: "claude-3-5-haiku-latest".to_string(),
+                max_tokens: 256,
+                temperature: None,
+                api_key_env: "NTANGLER_TEST_UNSET_KEY".to_string(),
+                version: "2023-06-01".to_string(),
+            }],
+            ..Default::default()
+        };
 
-#[derive(Deserialize)]
-struct CommitResponse {
-    commit_message: CommitMessage,
-}
+        let error = LlmClient::for_repositories(&[repository]).unwrap_err();
+
+        assert!(error.to_string().contains("tools"));
+        assert!(format!("{error:#}").contains("NTANGLER_TEST_UNSET_KEY is not set"));
+    }
 
-#[derive(Deserialize,Serialize)]
-struct CommitRequest {
-    diff: String,
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_generate_redacts_messages_and_examples() {
+        let reply = r#"{"type": "chore", "scope": "", "description": "rotate token", "body": "", "breaking": false}"#;
+        let (endpoint, server) = mock_server::serve_once(
+            200,
+            &serde_json::json!({ "message": { "role": "assistant", "content": reply }, "done": true }).to_string(),
+        )
+        .await;
+        let repository = NtangledRepository {
+            nickname: "tools".to_string(),
+            provider: ProviderConfig::Ollama { endpoint, model: "llama3.1:8b".to_string(), temperature: None, keep_alive: None },
+            redaction: RedactionConfig {
+                patterns: vec![RedactionPattern { pattern: "tok-[0-9]+".to_string(), replacement: "[token]".to_string() }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = LlmClient::for_repositories(&[repository]).unwrap();
+
+        let (_, metadata) = client
+            .generate("tools", None, "+TOKEN=tok-1\n".to_string(), vec!["chore: set tok-2".to_string()], vec!["chore: drop tok-3".to_string()])
+            .await
+            .unwrap();
+
+        let body = server.await.unwrap().body;
+        assert!(!body.contains("tok-"));
+        assert!(body.contains("chore: set [token]"));
+        assert!(body.contains("chore: drop [token]"));
+        assert_eq!(metadata.redactions, 3);
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::models::config::{ProviderConfig, RetryConfig};
+use crate::models::{CommitMessage, GenerationMetadata};
+
+use anthropic::AnthropicProvider;
+use heuristic::HeuristicProvider;
+use ntangler::NtanglerProvider;
+use ollama::OllamaProvider;
+use openai::OpenAiProvider;
+pub(crate) use prompt::COMMIT_TYPES;
+use retrying::RetryingProvider;
+pub(crate) use retrying::{failure_kind, Retried};
+
+mod anthropic;
+mod heuristic;
+#[cfg(test)]
+pub(super) mod mock_server;
+mod ntangler;
+mod ollama;
+mod openai;
+mod prompt;
+mod retrying;
+
+pub(crate) type ProviderFuture<'a> =
+    Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + Sync + 'a>>;
+
+/// What a provider is asked to write a message for.
+#[derive(Serialize, Debug, Clone, Default)]
+pub(crate) struct CommitRequest {
+    pub(crate) diff: String,
+    /// The messages of commits being combined, when regenerating a message for several of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) messages: Vec<String>,
+    /// Recent hand-written commit messages from the repository, for the new one to imitate.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) examples: Vec<String>,
+    /// The repository's prompt template, rendered for this change, when it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) prompt: Option<String>,
+}
+
+/// A provider's answer.
+#[derive(Debug)]
+pub(crate) struct ProviderResponse {
+    pub(crate) commit_message: CommitMessage,
+    /// The model the backend reports having used, when it says.
+    pub(crate) model: Option<String>,
+    /// The git blob hash of the request body that was sent.
+    pub(crate) prompt_hash: String,
+}
+
+/// A backend that writes commit messages.
+pub(crate) trait Provider: fmt::Debug + Send + Sync {
+    /// The `kind` the provider is configured with.
+    fn name(&self) -> &str;
+
+    /// Where requests go, for the generation note.
+    fn endpoint(&self) -> &str;
+
+    /// Whether messages are written by rules rather than a model.
+    fn heuristic(&self) -> bool {
+        false
+    }
+
+    fn generate<'a>(&'a self, request: &'a CommitRequest) -> ProviderFuture<'a>;
+}
+
+/// An error status from a provider's API.
+#[derive(Debug)]
+pub(crate) struct HttpError {
+    pub(crate) status: StatusCode,
+    /// How long the response asked callers to wait before trying again.
+    pub(crate) retry_after: Option<Duration>,
+    body: String,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Builds the provider a repository is configured with, retried and timed out as configured.
+pub(crate) fn from_config(
+    config: &ProviderConfig,
+    retry: &RetryConfig,
+    falls_back: bool,
+) -> anyhow::Result<Arc<dyn Provider>> {
+    let provider: Box<dyn Provider> = match config {
+        ProviderConfig::Ntangler { endpoint } => Box::new(NtanglerProvider::new(endpoint.clone())),
+        ProviderConfig::OpenAi {
+            endpoint,
+            model,
+            temperature,
+            api_key_env,
+            response_format,
+        } => Box::new(OpenAiProvider::new(
+            endpoint.clone(),
+            model.clone(),
+            *temperature,
+            api_key_env,
+            *response_format,
+        )?),
+        ProviderConfig::Ollama {
+            endpoint,
+            model,
+            temperature,
+            keep_alive,
+        } => Box::new(OllamaProvider::new(
+            endpoint.clone(),
+            model.clone(),
+            *temperature,
+            keep_alive.clone(),
+        )),
+        ProviderConfig::Anthropic {
+            endpoint,
+            model,
+            max_tokens,
+            temperature,
+            api_key_env,
+            version,
+        } => Box::new(AnthropicProvider::new(
+            endpoint.clone(),
+            model.clone(),
+            *max_tokens,
+            *temperature,
+            api_key_env,
+            version.clone(),
+        )?),
+        // Nothing to time out, retry or leave alone.
+        ProviderConfig::Heuristic => return Ok(Arc::new(HeuristicProvider)),
+    };
+    let provider = RetryingProvider::new(provider, retry.clone());
+    if falls_back {
+        return Ok(Arc::new(provider.falling_back()));
+    }
+    Ok(Arc::new(provider))
+}
+
+/// Sends `body` as JSON and reads back the response text, failing on an error status. The hash
+/// of the body comes back alongside it.
+async fn send_json<T: Serialize + ?Sized>(
+    request: RequestBuilder,
+    body: &T,
+) -> anyhow::Result<(String, String)> {
+    let body = serde_json::to_vec(body)?;
+    let prompt_hash = GenerationMetadata::hash_prompt(&body);
+    let response = request
+        .header(CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let retry_after = retry_after(response.headers());
+    let text = response.text().await?;
+    debug!("Response {status}: {text}");
+    if !status.is_success() {
+        return Err(HttpError {
+            status,
+            retry_after,
+            body: text.trim().to_string(),
+        }
+        .into());
+    }
+    Ok((prompt_hash, text))
+}
+
+/// Reads a `Retry-After` header given either in seconds or as a date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let when = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((when - Utc::now()).to_std().unwrap_or_default())
+}
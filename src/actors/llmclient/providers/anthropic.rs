@@ -0,0 +1,271 @@
+use std::env;
+
+use anyhow::{anyhow, Context};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::prompt;
+use super::{send_json, CommitRequest, Provider, ProviderFuture, ProviderResponse};
+
+/// The tool the model is made to call; its input is the commit message.
+const TOOL_NAME: &str = "commit_message";
+
+/// Anthropic's Messages API.
+#[derive(Debug, Default)]
+pub(crate) struct AnthropicProvider {
+    client: Client,
+    endpoint: String,
+    model: String,
+    max_tokens: u32,
+    temperature: Option<f32>,
+    api_key: String,
+    version: String,
+}
+
+#[derive(Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    system: String,
+    messages: [Message; 1],
+    tools: [Tool<'a>; 1],
+    tool_choice: ToolChoice<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    name: &'a str,
+    description: &'a str,
+    input_schema: Value,
+}
+
+#[derive(Serialize)]
+struct ToolChoice<'a> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    name: &'a str,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    model: Option<String>,
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    ToolUse {
+        name: String,
+        input: Value,
+    },
+    Text {
+        text: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+impl AnthropicProvider {
+    /// Reads the API key from `api_key_env`, failing when it is unset.
+    pub(crate) fn new(
+        endpoint: String,
+        model: String,
+        max_tokens: u32,
+        temperature: Option<f32>,
+        api_key_env: &str,
+        version: String,
+    ) -> anyhow::Result<AnthropicProvider> {
+        let api_key = env::var(api_key_env)
+            .map_err(|_| anyhow!("{api_key_env} is not set; the Anthropic API needs a key"))?;
+
+        Ok(AnthropicProvider {
+            client: Client::new(),
+            endpoint: format!("{}/v1/messages", endpoint.trim_end_matches('/')),
+            model,
+            max_tokens,
+            temperature,
+            api_key,
+            version,
+        })
+    }
+}
+
+impl Provider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn generate<'a>(&'a self, request: &'a CommitRequest) -> ProviderFuture<'a> {
+        Box::pin(async move {
+            let messages_request = MessagesRequest {
+                model: &self.model,
+                max_tokens: self.max_tokens,
+                system: prompt::system_prompt(request),
+                messages: [Message {
+                    role: "user",
+                    content: prompt::user_prompt(request),
+                }],
+                tools: [Tool {
+                    name: TOOL_NAME,
+                    description: "Record the commit message for the diff.",
+                    input_schema: prompt::commit_message_schema(),
+                }],
+                tool_choice: ToolChoice {
+                    kind: "tool",
+                    name: TOOL_NAME,
+                },
+                temperature: self.temperature,
+            };
+            let mut http_request = self
+                .client
+                .post(&self.endpoint)
+                .header("anthropic-version", &self.version)
+                .header("x-api-key", &self.api_key);
+            let (prompt_hash, body) = send_json(http_request, &messages_request).await?;
+
+            let response: MessagesResponse = serde_json::from_str(&body)?;
+            let mut text = String::new();
+            let mut input = None;
+            for block in response.content {
+                match block {
+                    ContentBlock::ToolUse { name, input: value } if name == TOOL_NAME => {
+                        input = Some(value);
+                    }
+                    ContentBlock::Text { text: block } => text.push_str(&block),
+                    _ => {}
+                }
+            }
+            // Without a tool call, a JSON object written out as text will do.
+            let commit_message = match input {
+                // CommitMessage borrows its keys, which a Value cannot lend.
+                Some(input) => serde_json::from_str(&input.to_string())?,
+                None => serde_json::from_str(prompt::json_content(&text))
+                    .with_context(|| format!("Model replied without a message: {text}"))?,
+            };
+
+            Ok(ProviderResponse {
+                commit_message,
+                model: response.model.or_else(|| Some(self.model.clone())),
+                prompt_hash,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::actors::llmclient::providers::mock_server;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_anthropic_provider_forces_tool_use() {
+        let (endpoint, server) = mock_server::serve_once(
+            200,
+            &json!({
+                "id": "msg_01",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-3-5-haiku-20241022",
+                "content": [{
+                    "type": "tool_use",
+                    "id": "toolu_01",
+                    "name": "commit_message",
+                    "input": {
+                        "type": "refactor",
+                        "scope": "watcher",
+                        "description": "debounce save events",
+                        "body": "",
+                        "breaking": false,
+                        "footers": []
+                    }
+                }],
+                "stop_reason": "tool_use",
+                "usage": { "input_tokens": 512, "output_tokens": 48 }
+            })
+            .to_string(),
+        )
+        .await;
+        env::set_var("NTANGLER_TEST_ANTHROPIC_KEY", "sk-ant-local");
+        let provider = AnthropicProvider::new(
+            endpoint,
+            "claude-3-5-haiku-latest".to_string(),
+            256,
+            None,
+            "NTANGLER_TEST_ANTHROPIC_KEY",
+            "2023-06-01".to_string(),
+        )
+        .unwrap();
+
+        let response = provider
+            .generate(&CommitRequest {
+                diff: "+debounce\n".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let received = server.await.unwrap();
+        assert_eq!(received.path, "/v1/messages");
+        assert_eq!(received.header("x-api-key"), Some("sk-ant-local"));
+        assert_eq!(received.header("anthropic-version"), Some("2023-06-01"));
+        let body = received.json();
+        assert_eq!(body["model"], "claude-3-5-haiku-latest");
+        assert_eq!(body["max_tokens"], 256);
+        assert_eq!(body["system"], prompt::SYSTEM_PROMPT);
+        assert_eq!(body["tools"][0]["name"], "commit_message");
+        assert_eq!(
+            body["tool_choice"],
+            json!({"type": "tool", "name": "commit_message"})
+        );
+        assert_eq!(body["messages"][0]["content"], "Diff:\n+debounce\n");
+        assert_eq!(
+            response.commit_message.to_string().lines().next(),
+            Some("refactor(watcher): debounce save events")
+        );
+        assert_eq!(response.model.as_deref(), Some("claude-3-5-haiku-20241022"));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_provider_reports_api_error() {
+        let (endpoint, _server) = mock_server::serve_once(
+            401,
+            r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#,
+        )
+        .await;
+        env::set_var("NTANGLER_TEST_ANTHROPIC_KEY", "sk-ant-local");
+        let provider = AnthropicProvider::new(
+            endpoint,
+            "claude-3-5-haiku-latest".to_string(),
+            256,
+            None,
+            "NTANGLER_TEST_ANTHROPIC_KEY",
+            "2023-06-01".to_string(),
+        )
+        .unwrap();
+
+        let error = provider
+            .generate(&CommitRequest::default())
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("401"));
+        assert!(error.to_string().contains("invalid x-api-key"));
+    }
+}
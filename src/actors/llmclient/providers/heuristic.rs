@@ -0,0 +1,363 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use anyhow::bail;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::models::{CommitMessage, Scope};
+
+use super::{CommitRequest, Provider, ProviderFuture, ProviderResponse};
+
+lazy_static! {
+    /// A line defining something worth naming, in the languages ntangler is most likely to see.
+    static ref DEFINITION: Regex = Regex::new(
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|async|unsafe|static|abstract|public|private|protected)\s+)*(?:fn|struct|enum|trait|type|mod|class|def|func|function|interface)\s+([A-Za-z_][A-Za-z0-9_]*)"
+    )
+    .unwrap();
+}
+
+/// How many changed symbols a description names before counting the rest.
+const MAX_SYMBOLS: usize = 3;
+
+/// Files that configure continuous integration, wherever they sit.
+const CI_FILES: &[&str] = &[
+    ".gitlab-ci.yml",
+    ".travis.yml",
+    ".drone.yml",
+    "appveyor.yml",
+    "azure-pipelines.yml",
+    "Jenkinsfile",
+];
+
+/// Directories holding nothing but continuous integration configuration.
+const CI_DIRS: &[&str] = &[".circleci", ".buildkite"];
+
+/// Lockfiles, which change when dependencies are resolved again rather than by hand.
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "uv.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "go.sum",
+];
+
+/// Files that say how a project is built and what it depends on.
+const BUILD_FILES: &[&str] = &[
+    "Cargo.toml",
+    "build.rs",
+    "rust-toolchain.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "go.mod",
+    "Gemfile",
+    "composer.json",
+    "Makefile",
+    "CMakeLists.txt",
+    "Dockerfile",
+];
+
+/// Directories too general to say what a change is about.
+const GENERIC_DIRS: &[&str] = &[
+    "src",
+    "lib",
+    "app",
+    "pkg",
+    "source",
+    "docs",
+    "doc",
+    "tests",
+    "test",
+    ".github",
+    "workflows",
+];
+
+/// Writes messages from the changed paths and lines alone, so commits keep coming when no model
+/// can be reached.
+#[derive(Debug, Default)]
+pub(crate) struct HeuristicProvider;
+
+impl Provider for HeuristicProvider {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn endpoint(&self) -> &str {
+        ""
+    }
+
+    fn heuristic(&self) -> bool {
+        true
+    }
+
+    fn generate<'a>(&'a self, request: &'a CommitRequest) -> ProviderFuture<'a> {
+        Box::pin(async move {
+            Ok(ProviderResponse {
+                commit_message: commit_message(&request.diff)?,
+                model: None,
+                prompt_hash: String::new(),
+            })
+        })
+    }
+}
+
+/// One file's part of a diff.
+#[derive(Debug, Default)]
+struct FileChange {
+    path: String,
+    added: bool,
+    deleted: bool,
+    insertions: usize,
+    deletions: usize,
+    /// Definitions on changed lines, in the order they appear.
+    symbols: Vec<String>,
+}
+
+impl FileChange {
+    fn note_symbol(&mut self, line: &str) {
+        let Some(symbol) = DEFINITION
+            .captures(line)
+            .map(|captures| captures[1].to_string())
+        else {
+            return;
+        };
+        if !self.symbols.contains(&symbol) {
+            self.symbols.push(symbol);
+        }
+    }
+}
+
+/// Describes a patch by which paths it touches and what it adds and removes from them.
+fn commit_message(diff: &str) -> anyhow::Result<CommitMessage> {
+    let files = parse(diff);
+    if files.is_empty() {
+        bail!("The diff names no files to describe");
+    }
+
+    Ok(CommitMessage {
+        commit_type: commit_type(&files).into(),
+        scope: scope(&files),
+        description: description(&files).as_str().into(),
+        body: body(&files),
+        ..Default::default()
+    })
+}
+
+fn parse(diff: &str) -> Vec<FileChange> {
+    let mut files: Vec<FileChange> = Vec::new();
+    let mut in_hunk = false;
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            let path = paths.rsplit_once(" b/").map_or(paths, |(_, path)| path);
+            files.push(FileChange {
+                path: path.to_string(),
+                ..Default::default()
+            });
+            in_hunk = false;
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with("@@") {
+            in_hunk = true;
+        } else if !in_hunk {
+            file.added |= line.starts_with("new file mode");
+            file.deleted |= line.starts_with("deleted file mode");
+        } else if let Some(content) = line.strip_prefix('+') {
+            file.insertions += 1;
+            file.note_symbol(content);
+        } else if let Some(content) = line.strip_prefix('-') {
+            file.deletions += 1;
+            file.note_symbol(content);
+        }
+    }
+    files
+}
+
+/// The type every file agrees on, or `chore` when they don't. Changes to code itself are
+/// `chore` too: telling a fix from a feature takes a model.
+fn commit_type(files: &[FileChange]) -> &'static str {
+    let mut types = files.iter().map(|file| path_type(&file.path));
+    let first = types.next().unwrap_or("chore");
+    if types.all(|commit_type| commit_type == first) {
+        first
+    } else {
+        "chore"
+    }
+}
+
+fn path_type(path: &str) -> &'static str {
+    let path = Path::new(path);
+    let name = path.file_name().and_then(OsStr::to_str).unwrap_or_default();
+    let stem = path.file_stem().and_then(OsStr::to_str).unwrap_or_default();
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let under = |names: &[&str]| {
+        directory
+            .components()
+            .any(|component| names.iter().any(|name| component.as_os_str() == *name))
+    };
+
+    if CI_FILES.contains(&name) || directory.starts_with(".github/workflows") || under(CI_DIRS) {
+        "ci"
+    } else if LOCKFILES.contains(&name) {
+        "chore"
+    } else if BUILD_FILES.contains(&name) {
+        "build"
+    } else if under(&["docs", "doc"]) || path.extension() == Some(OsStr::new("md")) {
+        "docs"
+    } else if under(&["tests", "test"])
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with(".test")
+        || stem.ends_with(".spec")
+    {
+        "test"
+    } else {
+        "chore"
+    }
+}
+
+/// The nearest directory containing every file that says something about it.
+fn scope(files: &[FileChange]) -> Option<Scope> {
+    let common = files
+        .iter()
+        .map(|file| Path::new(&file.path).parent().unwrap_or(Path::new("")))
+        .reduce(|common, directory| {
+            common
+                .ancestors()
+                .find(|ancestor| directory.starts_with(ancestor))
+                .unwrap_or(Path::new(""))
+        })?;
+    common
+        .components()
+        .rev()
+        .filter_map(|component| component.as_os_str().to_str())
+        .find(|name| !GENERIC_DIRS.contains(name))
+        .map(Scope::from)
+}
+
+fn description(files: &[FileChange]) -> String {
+    let subject = match files {
+        [file] => Path::new(&file.path)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .unwrap_or(&file.path)
+            .to_string(),
+        _ => format!("{} files", files.len()),
+    };
+    if files.iter().all(|file| file.added) {
+        return format!("add {subject}");
+    }
+    if files.iter().all(|file| file.deleted) {
+        return format!("remove {subject}");
+    }
+
+    let symbols: Vec<&str> = files
+        .iter()
+        .flat_map(|file| &file.symbols)
+        .map(String::as_str)
+        .collect();
+    if symbols.is_empty() {
+        return format!("update {subject}");
+    }
+    let mut named = symbols[..symbols.len().min(MAX_SYMBOLS)].join(", ");
+    if symbols.len() > MAX_SYMBOLS {
+        named = format!("{named} and {} more", symbols.len() - MAX_SYMBOLS);
+    }
+    format!("update {named} in {subject}")
+}
+
+fn body(files: &[FileChange]) -> String {
+    let insertions = files.iter().map(|file| file.insertions).sum();
+    let deletions = files.iter().map(|file| file.deletions).sum();
+    let listing = files
+        .iter()
+        .map(|file| {
+            format!(
+                "- {} (+{}, -{})",
+                file.path, file.insertions, file.deletions
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "{} changed, {}(+), {}(-):\n\n{listing}\n\nWritten by ntangler's offline heuristic; no model was consulted.",
+        counted(files.len(), "file"),
+        counted(insertions, "insertion"),
+        counted(deletions, "deletion"),
+    )
+}
+
+fn counted(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_type_from_paths() {
+        let cases = [
+            ("docs/setup.txt", "docs"),
+            ("README.md", "docs"),
+            ("tests/parse.rs", "test"),
+            ("src/parse_test.go", "test"),
+            ("Cargo.toml", "build"),
+            ("Cargo.lock", "chore"),
+            (".github/workflows/ci.yml", "ci"),
+            (".gitlab-ci.yml", "ci"),
+            ("src/main.rs", "chore"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(path_type(path), expected, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_message() {
+        let request = CommitRequest {
+            diff: "diff --git a/src/actors/parser.rs b/src/actors/parser.rs\n\
+                   index 1111111..2222222 100644\n\
+                   --- a/src/actors/parser.rs\n\
+                   +++ b/src/actors/parser.rs\n\
+                   @@ -1,3 +1,6 @@\n\
+                   -fn parse(input: &str) {}\n\
+                   +pub(crate) fn parse(input: &str) -> Token {}\n\
+                   +\n\
+                   +pub(crate) struct Token;\n\
+                   +// fn commented_out() {}\n"
+                .to_string(),
+            ..Default::default()
+        };
+
+        let response = HeuristicProvider.generate(&request).await.unwrap();
+
+        let message = response.commit_message;
+        assert_eq!(message.commit_type.to_string(), "chore");
+        assert_eq!(message.scope.as_deref(), Some("actors"));
+        assert_eq!(
+            message.description.to_string(),
+            "update parse, Token in parser.rs"
+        );
+        assert!(message.body.starts_with(
+            "1 file changed, 4 insertions(+), 1 deletion(-):\n\n- src/actors/parser.rs (+4, -1)"
+        ));
+        assert!(message.body.contains("heuristic"));
+        assert!(HeuristicProvider
+            .generate(&CommitRequest::default())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! A scripted HTTP server that stands in for a provider's API in tests.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// What the server was sent.
+#[derive(Debug)]
+pub(crate) struct ReceivedRequest {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    headers: Vec<(String, String)>,
+    pub(crate) body: String,
+}
+
+impl ReceivedRequest {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).expect("request body is JSON")
+    }
+}
+
+/// One scripted answer.
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    status: u16,
+    body: String,
+    headers: Vec<(String, String)>,
+    delay: Duration,
+}
+
+impl MockResponse {
+    pub(crate) fn new(status: u16, body: &str) -> MockResponse {
+        MockResponse {
+            status,
+            body: body.to_string(),
+            headers: Vec::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn header(mut self, name: &str, value: &str) -> MockResponse {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Holds the answer back for `delay`, to make the client time out.
+    pub(crate) fn delay(mut self, delay: Duration) -> MockResponse {
+        self.delay = delay;
+        self
+    }
+}
+
+/// Answers the first request to the returned base URL with `status` and `body`, and hands back
+/// what that request contained.
+pub(crate) async fn serve_once(status: u16, body: &str) -> (String, JoinHandle<ReceivedRequest>) {
+    let (endpoint, server) = serve(vec![MockResponse::new(status, body)]).await;
+    let server = tokio::spawn(async move { server.await.unwrap().remove(0) });
+    (endpoint, server)
+}
+
+/// Answers successive requests to the returned base URL with `responses` in order, and hands
+/// back what each request contained once all have been answered.
+pub(crate) async fn serve(
+    responses: Vec<MockResponse>,
+) -> (String, JoinHandle<Vec<ReceivedRequest>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let mut received = Vec::new();
+        let mut replies = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            received.push(read_request(&mut stream).await);
+            // Answered on the side, so a delayed answer doesn't hold up the next request.
+            replies.push(tokio::spawn(async move {
+                tokio::time::sleep(response.delay).await;
+                let mut head = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    response.status,
+                    response.body.len()
+                );
+                for (name, value) in &response.headers {
+                    head.push_str(&format!("{name}: {value}\r\n"));
+                }
+                head.push_str("\r\n");
+                // The client may have given up waiting.
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(response.body.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }));
+        }
+        for reply in replies {
+            reply.await.unwrap();
+        }
+        received
+    });
+
+    (format!("http://{address}"), server)
+}
+
+async fn read_request(stream: &mut TcpStream) -> ReceivedRequest {
+    let mut received = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let header_end = loop {
+        let read = stream.read(&mut buffer).await.unwrap();
+        assert!(read > 0, "connection closed before the request ended");
+        received.extend_from_slice(&buffer[..read]);
+        if let Some(position) = find(&received, b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&received[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or_default();
+    while received.len() < header_end + content_length {
+        let read = stream.read(&mut buffer).await.unwrap();
+        assert!(read > 0, "connection closed before the body ended");
+        received.extend_from_slice(&buffer[..read]);
+    }
+
+    ReceivedRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&received[header_end..]).to_string(),
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
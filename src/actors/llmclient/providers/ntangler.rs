@@ -0,0 +1,131 @@
+use std::env;
+
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{send_json, CommitRequest, Provider, ProviderFuture, ProviderResponse};
+
+/// ntangler's own protocol: POST the [`CommitRequest`] as is and read a commit message back.
+#[derive(Debug, Default)]
+pub(crate) struct NtanglerProvider {
+    client: Client,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+/// The model an endpoint reports having used, when it says.
+#[derive(Deserialize)]
+struct ResponseModel {
+    model: Option<String>,
+}
+
+impl NtanglerProvider {
+    /// Uses `endpoint`, or `NTANGLER_ENDPOINT` when it is not configured, with the token in
+    /// `NTANGLER_API_TOKEN`.
+    pub(crate) fn new(endpoint: Option<String>) -> NtanglerProvider {
+        let endpoint = endpoint
+            .or_else(|| env::var("NTANGLER_ENDPOINT").ok())
+            .unwrap_or_else(|| {
+                warn!("NTANGLER_ENDPOINT not set, using default endpoint");
+                "https://api.openai.com/v1".to_string()
+            });
+        let api_key = env::var("NTANGLER_API_TOKEN").ok();
+        if api_key.is_none() {
+            warn!("NTANGLER_API_TOKEN not set, API calls may fail");
+        }
+
+        NtanglerProvider {
+            client: Client::new(),
+            endpoint,
+            api_key,
+        }
+    }
+}
+
+impl Provider for NtanglerProvider {
+    fn name(&self) -> &str {
+        "ntangler"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn generate<'a>(&'a self, request: &'a CommitRequest) -> ProviderFuture<'a> {
+        Box::pin(async move {
+            let mut http_request = self.client.post(&self.endpoint);
+            if let Some(key) = &self.api_key {
+                http_request = http_request.bearer_auth(key);
+            }
+            let (prompt_hash, body) = send_json(http_request, request).await?;
+
+            Ok(ProviderResponse {
+                commit_message: serde_json::from_str(&body)?,
+                model: serde_json::from_str::<ResponseModel>(&body)
+                    .ok()
+                    .and_then(|response| response.model),
+                prompt_hash,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actors::llmclient::providers::mock_server;
+    use crate::models::GenerationMetadata;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ntangler_provider_round_trip() {
+        let (endpoint, server) = mock_server::serve_once(
+            200,
+            r#"{"type": "fix", "scope": "api", "description": "handle empty input", "body": "", "breaking": false, "model": "house-model"}"#,
+        )
+        .await;
+        let provider = NtanglerProvider {
+            client: Client::new(),
+            endpoint,
+            api_key: Some("token".to_string()),
+        };
+        let request = CommitRequest {
+            diff: "+fn parse() {}\n".to_string(),
+            ..Default::default()
+        };
+
+        let response = provider.generate(&request).await.unwrap();
+
+        let received = server.await.unwrap();
+        assert_eq!(received.method, "POST");
+        assert_eq!(received.header("authorization"), Some("Bearer token"));
+        assert_eq!(received.body, r#"{"diff":"+fn parse() {}\n"}"#);
+        assert_eq!(
+            response.prompt_hash,
+            GenerationMetadata::hash_prompt(received.body.as_bytes())
+        );
+        assert_eq!(response.model.as_deref(), Some("house-model"));
+        assert_eq!(
+            response.commit_message.description.to_string(),
+            "handle empty input"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ntangler_provider_error_status() {
+        let (endpoint, _server) = mock_server::serve_once(503, r#"{"error": "overloaded"}"#).await;
+        let provider = NtanglerProvider {
+            endpoint,
+            ..Default::default()
+        };
+
+        let error = provider
+            .generate(&CommitRequest::default())
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("503"));
+        assert!(error.to_string().contains("overloaded"));
+    }
+}
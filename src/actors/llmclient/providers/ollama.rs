@@ -0,0 +1,198 @@
+use anyhow::Context;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::prompt;
+use super::{send_json, CommitRequest, Provider, ProviderFuture, ProviderResponse};
+
+/// Ollama's chat endpoint, for models running on the same machine.
+#[derive(Debug, Default)]
+pub(crate) struct OllamaProvider {
+    client: Client,
+    endpoint: String,
+    model: String,
+    temperature: Option<f32>,
+    keep_alive: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    format: &'a str,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<Options>,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct Options {
+    temperature: f32,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    model: Option<String>,
+    message: Option<ResponseMessage>,
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+impl OllamaProvider {
+    pub(crate) fn new(
+        endpoint: String,
+        model: String,
+        temperature: Option<f32>,
+        keep_alive: Option<String>,
+    ) -> OllamaProvider {
+        OllamaProvider {
+            client: Client::new(),
+            endpoint: format!("{}/api/chat", endpoint.trim_end_matches('/')),
+            model,
+            temperature,
+            keep_alive,
+        }
+    }
+}
+
+impl Provider for OllamaProvider {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn generate<'a>(&'a self, request: &'a CommitRequest) -> ProviderFuture<'a> {
+        Box::pin(async move {
+            let chat_request = ChatRequest {
+                model: &self.model,
+                messages: [
+                    ChatMessage {
+                        role: "system",
+                        content: prompt::system_prompt(request),
+                    },
+                    ChatMessage {
+                        role: "user",
+                        content: prompt::user_prompt(request),
+                    },
+                ],
+                format: "json",
+                stream: false,
+                keep_alive: self.keep_alive.as_deref(),
+                options: self.temperature.map(|temperature| Options { temperature }),
+            };
+            let (prompt_hash, body) =
+                send_json(self.client.post(&self.endpoint), &chat_request).await?;
+
+            let response: ChatResponse = serde_json::from_str(&body)?;
+            let content = response
+                .message
+                .context("Ollama returned no message")?
+                .content;
+            let commit_message = serde_json::from_str(prompt::json_content(&content))
+                .with_context(|| format!("Model replied with an unusable message: {content}"))?;
+
+            Ok(ProviderResponse {
+                commit_message,
+                model: response.model.or_else(|| Some(self.model.clone())),
+                prompt_hash,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::actors::llmclient::providers::mock_server;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ollama_provider_round_trip() {
+        let reply = json!({
+            "type": "docs",
+            "scope": null,
+            "description": "explain offline setup",
+            "body": "",
+            "breaking": false,
+            "footers": []
+        });
+        let (endpoint, server) = mock_server::serve_once(
+            200,
+            &json!({
+                "model": "llama3.1:8b",
+                "created_at": "2024-07-22T20:33:28.123648Z",
+                "message": { "role": "assistant", "content": reply.to_string() },
+                "done_reason": "stop",
+                "done": true,
+                "total_duration": 4883583458u64,
+                "eval_count": 40
+            })
+            .to_string(),
+        )
+        .await;
+        let provider = OllamaProvider::new(
+            format!("{endpoint}/"),
+            "llama3.1:8b".to_string(),
+            Some(0.1),
+            Some("30m".to_string()),
+        );
+
+        let response = provider
+            .generate(&CommitRequest {
+                diff: "+## Offline\n".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let received = server.await.unwrap();
+        assert_eq!(received.path, "/api/chat");
+        assert_eq!(received.header("authorization"), None);
+        let body = received.json();
+        assert_eq!(body["model"], "llama3.1:8b");
+        assert_eq!(body["format"], "json");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["keep_alive"], "30m");
+        assert!(body["options"]["temperature"].is_number());
+        assert_eq!(body["messages"][1]["content"], "Diff:\n+## Offline\n");
+        assert_eq!(
+            response.commit_message.to_string().lines().next(),
+            Some("docs: explain offline setup")
+        );
+        assert_eq!(response.model.as_deref(), Some("llama3.1:8b"));
+    }
+
+    #[tokio::test]
+    async fn test_ollama_provider_reports_missing_model() {
+        let (endpoint, _server) = mock_server::serve_once(
+            404,
+            r#"{"error":"model \"llama9\" not found, try pulling it first"}"#,
+        )
+        .await;
+        let provider = OllamaProvider::new(endpoint, "llama9".to_string(), None, None);
+
+        let error = provider
+            .generate(&CommitRequest::default())
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("404"));
+        assert!(error.to_string().contains("try pulling it first"));
+    }
+}
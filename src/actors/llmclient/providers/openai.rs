@@ -0,0 +1,252 @@
+use std::env;
+
+use anyhow::{anyhow, Context};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::models::config::ResponseFormat;
+
+use super::prompt;
+use super::{send_json, CommitRequest, Provider, ProviderFuture, ProviderResponse};
+
+/// OpenAI's Chat Completions API, which most self-hosted model servers also speak.
+#[derive(Debug, Default)]
+pub(crate) struct OpenAiProvider {
+    client: Client,
+    endpoint: String,
+    model: String,
+    temperature: Option<f32>,
+    api_key: Option<String>,
+    response_format: ResponseFormat,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    model: Option<String>,
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChoiceMessage {
+    content: Option<String>,
+    refusal: Option<String>,
+}
+
+impl OpenAiProvider {
+    /// Reads the API key from `api_key_env`, failing when it is unset. An empty `api_key_env`
+    /// goes without one, for local servers that take no key.
+    pub(crate) fn new(
+        endpoint: String,
+        model: String,
+        temperature: Option<f32>,
+        api_key_env: &str,
+        response_format: ResponseFormat,
+    ) -> anyhow::Result<OpenAiProvider> {
+        let api_key = match api_key_env {
+            "" => None,
+            api_key_env => Some(env::var(api_key_env).map_err(|_| {
+                anyhow!("{api_key_env} is not set; set it, or set api_key_env = \"\" if {endpoint} takes no key")
+            })?),
+        };
+
+        Ok(OpenAiProvider {
+            client: Client::new(),
+            endpoint: format!("{}/chat/completions", endpoint.trim_end_matches('/')),
+            model,
+            temperature,
+            api_key,
+            response_format,
+        })
+    }
+
+    fn response_format(&self) -> Option<Value> {
+        match self.response_format {
+            ResponseFormat::JsonSchema => Some(json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "commit_message",
+                    "strict": true,
+                    "schema": prompt::commit_message_schema(),
+                },
+            })),
+            ResponseFormat::JsonObject => Some(json!({ "type": "json_object" })),
+            ResponseFormat::None => None,
+        }
+    }
+}
+
+impl Provider for OpenAiProvider {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn generate<'a>(&'a self, request: &'a CommitRequest) -> ProviderFuture<'a> {
+        Box::pin(async move {
+            let chat_request = ChatRequest {
+                model: &self.model,
+                messages: [
+                    ChatMessage {
+                        role: "system",
+                        content: prompt::system_prompt(request),
+                    },
+                    ChatMessage {
+                        role: "user",
+                        content: prompt::user_prompt(request),
+                    },
+                ],
+                temperature: self.temperature,
+                response_format: self.response_format(),
+            };
+            let mut http_request = self.client.post(&self.endpoint);
+            if let Some(key) = &self.api_key {
+                http_request = http_request.bearer_auth(key);
+            }
+            let (prompt_hash, body) = send_json(http_request, &chat_request).await?;
+
+            let response: ChatResponse = serde_json::from_str(&body)?;
+            let message = response
+                .choices
+                .into_iter()
+                .next()
+                .context("Chat completion returned no choices")?
+                .message;
+            let content = match (message.content, message.refusal) {
+                (Some(content), _) => content,
+                (None, Some(refusal)) => anyhow::bail!("Model refused: {refusal}"),
+                (None, None) => anyhow::bail!("Chat completion returned no content"),
+            };
+            let commit_message = serde_json::from_str(prompt::json_content(&content))
+                .with_context(|| format!("Model replied with an unusable message: {content}"))?;
+
+            Ok(ProviderResponse {
+                commit_message,
+                model: response.model.or_else(|| Some(self.model.clone())),
+                prompt_hash,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actors::llmclient::providers::mock_server;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_openai_provider_requests_structured_output() {
+        let reply = json!({
+            "type": "feat",
+            "scope": "parser",
+            "description": "accept trailing commas",
+            "body": "",
+            "breaking": false,
+            "footers": []
+        });
+        let (endpoint, server) = mock_server::serve_once(
+            200,
+            &json!({
+                "id": "chatcmpl-1",
+                "model": "qwen2.5-coder-7b-instruct",
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": reply.to_string() },
+                    "finish_reason": "stop"
+                }]
+            })
+            .to_string(),
+        )
+        .await;
+        env::set_var("NTANGLER_TEST_OPENAI_KEY", "sk-local");
+        let provider = OpenAiProvider::new(
+            format!("{endpoint}/v1/"),
+            "qwen2.5-coder-7b-instruct".to_string(),
+            Some(0.2),
+            "NTANGLER_TEST_OPENAI_KEY",
+            ResponseFormat::JsonSchema,
+        )
+        .unwrap();
+
+        let response = provider
+            .generate(&CommitRequest {
+                diff: "+trailing,\n".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let received = server.await.unwrap();
+        assert_eq!(received.path, "/v1/chat/completions");
+        assert_eq!(received.header("authorization"), Some("Bearer sk-local"));
+        let body = received.json();
+        assert_eq!(body["model"], "qwen2.5-coder-7b-instruct");
+        assert_eq!(
+            body["temperature"].as_f64().map(|t| (t * 10.0).round()),
+            Some(2.0)
+        );
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "Diff:\n+trailing,\n");
+        assert_eq!(body["response_format"]["type"], "json_schema");
+        assert_eq!(
+            body["response_format"]["json_schema"]["schema"]["required"][0],
+            "type"
+        );
+        assert_eq!(
+            response.commit_message.to_string().lines().next(),
+            Some("feat(parser): accept trailing commas")
+        );
+        assert_eq!(response.model.as_deref(), Some("qwen2.5-coder-7b-instruct"));
+    }
+
+    #[tokio::test]
+    async fn test_openai_provider_rejects_unparseable_reply() {
+        let (endpoint, _server) = mock_server::serve_once(
+            200,
+            r#"{"choices": [{"message": {"content": "Sure! Here is a commit message."}}]}"#,
+        )
+        .await;
+        let provider = OpenAiProvider::new(
+            endpoint,
+            "gpt-4o-mini".to_string(),
+            None,
+            "",
+            ResponseFormat::None,
+        )
+        .unwrap();
+
+        let error = provider
+            .generate(&CommitRequest::default())
+            .await
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("Sure! Here is a commit message."));
+    }
+}
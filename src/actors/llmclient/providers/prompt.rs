@@ -0,0 +1,141 @@
+//! The instructions shared by providers that talk to a general-purpose chat model.
+
+use serde_json::{json, Value};
+
+use super::CommitRequest;
+
+/// The commit types messages may use.
+pub(crate) const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+pub(crate) const SYSTEM_PROMPT: &str = "\
+You write Conventional Commits messages for the diff you are given. Reply with a single JSON \
+object and nothing else, with these fields:
+- \"type\": one of feat, fix, docs, style, refactor, perf, test, build, ci, chore or revert.
+- \"scope\": the area of the code the change touches, in lower case, or null.
+- \"description\": an imperative summary in lower case with no trailing period, under 60 characters.
+- \"body\": what changed and why, wrapped at 72 characters, or an empty string for trivial changes.
+- \"breaking\": true only if the change breaks existing users.
+- \"footers\": an array of {\"token\": ..., \"value\": ...} objects, usually empty.";
+
+/// The instructions, followed by the repository's recent messages when there are examples to
+/// imitate.
+pub(crate) fn system_prompt(request: &CommitRequest) -> String {
+    if request.examples.is_empty() {
+        return SYSTEM_PROMPT.to_string();
+    }
+    format!(
+        "{SYSTEM_PROMPT}\n\nMatch the tone, scope names and body length of these recent commit \
+         messages from the same project:\n{}---",
+        separated(&request.examples)
+    )
+}
+
+/// The user turn: the repository's rendered template or else the diff, preceded by the messages
+/// it replaces when there are any.
+pub(crate) fn user_prompt(request: &CommitRequest) -> String {
+    let prompt = match &request.prompt {
+        Some(prompt) => prompt.clone(),
+        None if request.messages.is_empty() => format!("Diff:\n{}", request.diff),
+        None => format!("Combined diff:\n{}", request.diff),
+    };
+    if request.messages.is_empty() {
+        return prompt;
+    }
+    format!(
+        "These commits are being combined into one. Their messages were:\n{}---\n\n{prompt}",
+        separated(&request.messages)
+    )
+}
+
+/// Commit messages, each preceded by a `---` line.
+fn separated(messages: &[String]) -> String {
+    messages
+        .iter()
+        .map(|message| format!("---\n{}\n", message.trim()))
+        .collect()
+}
+
+/// A JSON schema for the object [`SYSTEM_PROMPT`] asks for, in the strict form structured
+/// output requires: every property listed and nothing else allowed.
+pub(crate) fn commit_message_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "type": {
+                "type": "string",
+                "enum": COMMIT_TYPES
+            },
+            "scope": { "type": ["string", "null"] },
+            "description": { "type": "string" },
+            "body": { "type": "string" },
+            "breaking": { "type": "boolean" },
+            "footers": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "token": { "type": "string" },
+                        "value": { "type": "string" }
+                    },
+                    "required": ["token", "value"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["type", "scope", "description", "body", "breaking", "footers"],
+        "additionalProperties": false
+    })
+}
+
+/// The JSON object in a model's reply, which some wrap in a Markdown code fence despite being
+/// asked not to.
+pub(crate) fn json_content(reply: &str) -> &str {
+    let reply = reply.trim();
+    match reply.strip_prefix("```") {
+        Some(fenced) => fenced
+            .trim_start_matches("json")
+            .trim_end_matches("```")
+            .trim(),
+        None => reply,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_prompt_includes_style_examples() {
+        let request = CommitRequest {
+            examples: vec!["docs(readme): explain setup\n\nMention the config path.\n".to_string()],
+            ..Default::default()
+        };
+
+        assert!(system_prompt(&request).starts_with(SYSTEM_PROMPT));
+        assert!(system_prompt(&request).ends_with(
+            "from the same project:\n---\ndocs(readme): explain setup\n\nMention the config path.\n---"
+        ));
+    }
+
+    #[test]
+    fn test_user_prompt_lists_replaced_messages() {
+        let request = CommitRequest {
+            diff: "+b\n".to_string(),
+            messages: vec!["feat: add a\n".to_string(), "fix: repair a".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            user_prompt(&request),
+            "These commits are being combined into one. Their messages were:\n\
+             ---\nfeat: add a\n---\nfix: repair a\n---\n\nCombined diff:\n+b\n"
+        );
+        assert_eq!(system_prompt(&request), SYSTEM_PROMPT);
+        assert_eq!(
+            json_content("```json\n{\"type\": \"fix\"}\n```\n"),
+            "{\"type\": \"fix\"}"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use std::fmt;
+use std::time::Duration;
+
+use failsafe::backoff::{self, Exponential};
+use failsafe::failure_policy::{self, ConsecutiveFailures};
+use failsafe::{Config, StateMachine};
+use tracing::warn;
+
+use crate::models::config::RetryConfig;
+use crate::models::FailureKind;
+
+use super::{CommitRequest, HttpError, Provider, ProviderFuture};
+
+type Breaker = StateMachine<ConsecutiveFailures<Exponential>, ()>;
+
+/// The provider was skipped because its circuit is open after repeated failures.
+#[derive(Debug)]
+pub(crate) struct CircuitOpen {
+    endpoint: String,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} keeps failing; not calling it for a while",
+            self.endpoint
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// The provider took longer to answer than the configured timeout.
+#[derive(Debug)]
+pub(crate) struct TimedOut {
+    after: Duration,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No response within {}s", self.after.as_secs())
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Added to the last error when the request was retried before giving up.
+#[derive(Debug)]
+pub(crate) struct Retried {
+    pub(crate) retries: u32,
+}
+
+impl fmt::Display for Retried {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.retries == 1 {
+            "retry"
+        } else {
+            "retries"
+        };
+        write!(f, "Gave up after {} {plural}", self.retries)
+    }
+}
+
+impl std::error::Error for Retried {}
+
+/// Wraps a provider with a timeout on each attempt, retries with exponential backoff on
+/// failures that may pass, and a circuit breaker that stops calling it after repeated ones.
+pub(crate) struct RetryingProvider {
+    inner: Box<dyn Provider>,
+    config: RetryConfig,
+    breaker: Breaker,
+    /// Another provider follows in the chain, so a timeout falls through to it rather than
+    /// being waited out again.
+    falls_back: bool,
+}
+
+impl fmt::Debug for RetryingProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryingProvider")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .field("falls_back", &self.falls_back)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryingProvider {
+    pub(crate) fn new(inner: Box<dyn Provider>, config: RetryConfig) -> RetryingProvider {
+        let open_for = Duration::from_secs(config.open_for);
+        let policy = failure_policy::consecutive_failures(
+            config.failure_threshold.max(1),
+            backoff::exponential(open_for, open_for.saturating_mul(10)),
+        );
+        RetryingProvider {
+            inner,
+            config,
+            breaker: Config::new().failure_policy(policy).build(),
+            falls_back: false,
+        }
+    }
+
+    /// Gives up on the first timeout, for a provider with another after it in the chain.
+    pub(crate) fn falling_back(mut self) -> RetryingProvider {
+        self.falls_back = true;
+        self
+    }
+}
+
+impl Provider for RetryingProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn endpoint(&self) -> &str {
+        self.inner.endpoint()
+    }
+
+    fn heuristic(&self) -> bool {
+        self.inner.heuristic()
+    }
+
+    fn generate<'a>(&'a self, request: &'a CommitRequest) -> ProviderFuture<'a> {
+        Box::pin(async move {
+            let mut retry = 0;
+            loop {
+                if !self.breaker.is_call_permitted() {
+                    return Err(CircuitOpen {
+                        endpoint: self.endpoint().to_string(),
+                    }
+                    .into());
+                }
+                let timeout = self.config.timeout();
+                let error = match tokio::time::timeout(timeout, self.inner.generate(request)).await
+                {
+                    Ok(Ok(response)) => {
+                        self.breaker.on_success();
+                        return Ok(response);
+                    }
+                    Ok(Err(error)) => error,
+                    Err(_) => TimedOut { after: timeout }.into(),
+                };
+
+                let kind = failure_kind(&error);
+                if !matches!(
+                    kind,
+                    FailureKind::Timeout
+                        | FailureKind::Unreachable
+                        | FailureKind::RateLimited
+                        | FailureKind::ServerError
+                ) {
+                    return Err(gave_up(error, retry));
+                }
+                self.breaker.on_error();
+                if retry >= self.config.max_retries
+                    || (self.falls_back && kind == FailureKind::Timeout)
+                {
+                    return Err(gave_up(error, retry));
+                }
+                let wait = error
+                    .downcast_ref::<HttpError>()
+                    .and_then(|error| error.retry_after)
+                    .unwrap_or_else(|| self.config.backoff(retry));
+                // Asked to wait longer than we would, so trying again early would only be refused.
+                if wait > Duration::from_secs(self.config.max_backoff) {
+                    return Err(gave_up(error, retry));
+                }
+                retry += 1;
+                warn!(
+                    endpoint = self.endpoint(),
+                    "{error}; retrying in {}ms ({retry}/{})",
+                    wait.as_millis(),
+                    self.config.max_retries
+                );
+                tokio::time::sleep(wait).await;
+            }
+        })
+    }
+}
+
+fn gave_up(error: anyhow::Error, retries: u32) -> anyhow::Error {
+    match retries {
+        0 => error,
+        retries => error.context(Retried { retries }),
+    }
+}
+
+/// Sorts a provider's error by cause. Timeouts, connection failures, rate limits and server
+/// errors may pass and are worth retrying; anything else would fail the same way again.
+pub(crate) fn failure_kind(error: &anyhow::Error) -> FailureKind {
+    if error.is::<TimedOut>() {
+        return FailureKind::Timeout;
+    }
+    if error.is::<CircuitOpen>() {
+        return FailureKind::CircuitOpen;
+    }
+    if let Some(error) = error.downcast_ref::<HttpError>() {
+        return match error.status.as_u16() {
+            429 => FailureKind::RateLimited,
+            500..=599 => FailureKind::ServerError,
+            _ => FailureKind::Rejected,
+        };
+    }
+    match error.downcast_ref::<reqwest::Error>() {
+        Some(error) if error.is_timeout() => FailureKind::Timeout,
+        Some(error) if error.is_connect() || error.is_request() || error.is_body() => {
+            FailureKind::Unreachable
+        }
+        _ => FailureKind::InvalidResponse,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::actors::llmclient::providers::mock_server::{self, MockResponse};
+    use crate::actors::llmclient::providers::ntangler::NtanglerProvider;
+
+    use super::*;
+
+    fn commit_message() -> String {
+        json!({
+            "type": "fix",
+            "scope": null,
+            "description": "retry flaky requests",
+            "body": "",
+            "breaking": false
+        })
+        .to_string()
+    }
+
+    fn retrying(endpoint: String, config: RetryConfig) -> RetryingProvider {
+        RetryingProvider::new(Box::new(NtanglerProvider::new(Some(endpoint))), config)
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limits_honoring_retry_after() {
+        let (endpoint, server) = mock_server::serve(vec![
+            MockResponse::new(429, r#"{"error": "slow down"}"#).header("Retry-After", "0"),
+            MockResponse::new(503, "unavailable"),
+            MockResponse::new(200, &commit_message()),
+        ])
+        .await;
+        let provider = retrying(
+            endpoint,
+            RetryConfig {
+                backoff: 1,
+                ..Default::default()
+            },
+        );
+
+        let response = provider.generate(&CommitRequest::default()).await.unwrap();
+
+        assert_eq!(server.await.unwrap().len(), 3);
+        assert_eq!(
+            response.commit_message.description.to_string(),
+            "retry flaky requests"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        let (endpoint, server) = mock_server::serve(vec![
+            MockResponse::new(400, "bad request"),
+            MockResponse::new(200, &commit_message()),
+        ])
+        .await;
+        let provider = retrying(endpoint, RetryConfig::default());
+
+        let error = provider
+            .generate(&CommitRequest::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.downcast_ref::<HttpError>().unwrap().status, 400);
+        assert_eq!(failure_kind(&error), FailureKind::Rejected);
+        assert!(!error.is::<Retried>());
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_times_out_and_opens_circuit() {
+        let (endpoint, server) = mock_server::serve(vec![
+            MockResponse::new(200, &commit_message()).delay(Duration::from_secs(5)),
+            MockResponse::new(502, "bad gateway"),
+        ])
+        .await;
+        let provider = retrying(
+            endpoint,
+            RetryConfig {
+                timeout: 1,
+                max_retries: 1,
+                backoff: 1,
+                failure_threshold: 2,
+                open_for: 60,
+                ..Default::default()
+            },
+        );
+
+        let error = provider
+            .generate(&CommitRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(failure_kind(&error), FailureKind::ServerError);
+        assert_eq!(error.downcast_ref::<Retried>().unwrap().retries, 1);
+
+        let error = provider
+            .generate(&CommitRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(failure_kind(&error), FailureKind::CircuitOpen);
+        server.abort();
+    }
+}
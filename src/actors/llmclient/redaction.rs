@@ -0,0 +1,245 @@
+use std::borrow::Cow;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::Utc;
+use git2::Repository;
+use regex::{NoExpand, Regex};
+
+use crate::models::config::RedactionConfig;
+
+/// What one rule took out of one file of a diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Redaction {
+    pub(crate) file: String,
+    pub(crate) rule: String,
+    pub(crate) count: usize,
+}
+
+/// A repository's redaction rules, ready to apply to diffs on their way to the LLM.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Redactor {
+    paths: Vec<(String, Regex)>,
+    patterns: Vec<(Regex, String)>,
+    audit_log: Option<PathBuf>,
+}
+
+impl Redactor {
+    pub(crate) fn new(
+        config: &RedactionConfig,
+        repository_path: &Path,
+    ) -> anyhow::Result<Redactor> {
+        let paths = config
+            .paths
+            .iter()
+            .map(|glob| Ok((glob.clone(), glob_regex(glob)?)))
+            .collect::<anyhow::Result<_>>()?;
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern)
+                    .with_context(|| format!("Invalid redaction pattern {}", rule.pattern))?;
+                Ok((regex, rule.replacement.clone()))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let audit_log = Repository::open(repository_path)
+            .ok()
+            .map(|repo| repo.path().join("ntangler").join("redactions"));
+        Ok(Redactor {
+            paths,
+            patterns,
+            audit_log,
+        })
+    }
+
+    /// Withholds the changes to files matching a path rule and replaces pattern matches, one
+    /// line at a time.
+    pub(crate) fn redact(&self, diff: &str) -> (String, Vec<Redaction>) {
+        let mut redacted = String::with_capacity(diff.len());
+        let mut redactions: Vec<Redaction> = Vec::new();
+        let mut file = String::new();
+        let mut withheld = false;
+        for line in diff.split_inclusive('\n') {
+            if let Some(path) = header_path(line) {
+                file = path.to_string();
+                withheld = false;
+                if let Some((glob, _)) = self.paths.iter().find(|(_, regex)| regex.is_match(&file))
+                {
+                    withheld = true;
+                    redacted.push_str(line);
+                    redacted.push_str(&format!("[changes to {file} withheld]\n"));
+                    note(&mut redactions, &file, glob, 1);
+                    continue;
+                }
+            }
+            if withheld {
+                continue;
+            }
+
+            let mut line = Cow::Borrowed(line);
+            for (regex, replacement) in &self.patterns {
+                let count = regex.find_iter(&line).count();
+                if count > 0 {
+                    line = Cow::Owned(regex.replace_all(&line, NoExpand(replacement)).into_owned());
+                    note(&mut redactions, &file, regex.as_str(), count);
+                }
+            }
+            redacted.push_str(&line);
+        }
+        (redacted, redactions)
+    }
+
+    /// Appends which rules fired on which files, but never the text they removed, to
+    /// `.git/ntangler/redactions`.
+    pub(crate) fn audit(&self, redactions: &[Redaction]) -> anyhow::Result<()> {
+        let Some(audit_log) = &self.audit_log else {
+            return Ok(());
+        };
+        if redactions.is_empty() {
+            return Ok(());
+        }
+        if let Some(directory) = audit_log.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(audit_log)?;
+        let now = Utc::now().to_rfc3339();
+        for redaction in redactions {
+            writeln!(
+                log,
+                "{now}\t{}\t{}\t{}",
+                redaction.file, redaction.rule, redaction.count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn note(redactions: &mut Vec<Redaction>, file: &str, rule: &str, count: usize) {
+    match redactions
+        .iter_mut()
+        .find(|redaction| redaction.file == file && redaction.rule == rule)
+    {
+        Some(redaction) => redaction.count += count,
+        None => redactions.push(Redaction {
+            file: file.to_string(),
+            rule: rule.to_string(),
+            count,
+        }),
+    }
+}
+
+/// The path a `diff --git a/<path> b/<path>` header introduces.
+fn header_path(line: &str) -> Option<&str> {
+    let (_, path) = line
+        .strip_prefix("diff --git a/")?
+        .trim_end()
+        .rsplit_once(" b/")?;
+    Some(path)
+}
+
+/// Translates a gitignore-style glob into a regex over repository-relative paths. A glob
+/// without a slash matches a file name in any directory, and one naming a directory covers
+/// everything under it.
+fn glob_regex(glob: &str) -> anyhow::Result<Regex> {
+    let trimmed = glob.trim_matches('/');
+    let mut regex = String::from(if trimmed.contains('/') { "^" } else { "(^|/)" });
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push_str("(/.*)?$");
+    Regex::new(&regex).with_context(|| format!("Invalid redaction path {glob}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::config::RedactionPattern;
+
+    use super::*;
+
+    #[test]
+    fn test_glob_regex() {
+        let glob = glob_regex("customers/**").unwrap();
+        assert!(glob.is_match("customers/acme/contract.md"));
+        assert!(!glob.is_match("src/customers.rs"));
+
+        let glob = glob_regex("*.csv").unwrap();
+        assert!(glob.is_match("exports/2024/q1.csv"));
+        assert!(!glob.is_match("exports/q1.csv.md"));
+
+        let glob = glob_regex("config/**/secrets.toml").unwrap();
+        assert!(glob.is_match("config/secrets.toml"));
+        assert!(glob.is_match("config/prod/eu/secrets.toml"));
+    }
+
+    #[test]
+    fn test_redact_diff() {
+        let config = RedactionConfig {
+            paths: vec!["customers/**".to_string()],
+            patterns: vec![RedactionPattern {
+                pattern: "CUST-[0-9]{6}".to_string(),
+                replacement: "<customer-id>".to_string(),
+            }],
+        };
+        let dir = tempfile::TempDir::new().unwrap();
+        Repository::init(dir.path()).unwrap();
+        let redactor = Redactor::new(&config, dir.path()).unwrap();
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n\
+                    @@ -1 +1 @@\n\
+                    let id = \"CUST-123456\"; // was CUST-654321\n\
+                    diff --git a/customers/acme.md b/customers/acme.md\n\
+                    @@ -1 +1 @@\n\
+                    Acme owes $1M\n";
+
+        let (redacted, redactions) = redactor.redact(diff);
+
+        assert_eq!(
+            redacted,
+            "diff --git a/src/lib.rs b/src/lib.rs\n\
+             @@ -1 +1 @@\n\
+             let id = \"<customer-id>\"; // was <customer-id>\n\
+             diff --git a/customers/acme.md b/customers/acme.md\n\
+             [changes to customers/acme.md withheld]\n"
+        );
+        assert_eq!(
+            redactions,
+            vec![
+                Redaction {
+                    file: "src/lib.rs".to_string(),
+                    rule: "CUST-[0-9]{6}".to_string(),
+                    count: 2,
+                },
+                Redaction {
+                    file: "customers/acme.md".to_string(),
+                    rule: "customers/**".to_string(),
+                    count: 1,
+                },
+            ]
+        );
+
+        redactor.audit(&redactions).unwrap();
+        let audit = fs::read_to_string(dir.path().join(".git/ntangler/redactions")).unwrap();
+        assert!(audit.contains("\tsrc/lib.rs\tCUST-[0-9]{6}\t2\n"));
+        assert!(audit.contains("\tcustomers/acme.md\tcustomers/**\t1\n"));
+        assert!(!audit.contains("123456"));
+    }
+}
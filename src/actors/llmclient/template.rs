@@ -0,0 +1,231 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use git2::Repository;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::actors::GitRepository;
+use crate::models::NtangledRepository;
+
+use super::providers::COMMIT_TYPES;
+
+/// How many subjects `{{recent_commits}}` lists.
+const RECENT_COMMITS: usize = 10;
+
+const PLACEHOLDERS: &[&str] = &[
+    "diff",
+    "file",
+    "repository",
+    "branch",
+    "recent_commits",
+    "commit_types",
+    "language",
+];
+
+lazy_static! {
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{\{\s*([A-Za-z_]+)\s*\}\}").unwrap();
+}
+
+/// A repository's own prompt, rendered with what is known about each change before it is sent.
+#[derive(Debug, Clone)]
+pub(crate) struct PromptTemplate {
+    source: String,
+    nickname: String,
+    path: PathBuf,
+    branch: String,
+}
+
+impl PromptTemplate {
+    /// Reads the repository's template, resolving a relative path against the repository, and
+    /// checks that it only uses known placeholders.
+    pub(crate) fn load(repository: &NtangledRepository) -> anyhow::Result<Option<PromptTemplate>> {
+        let Some(template_path) = &repository.prompt_template else {
+            return Ok(None);
+        };
+        let template_path = repository.path.join(template_path);
+        let source = fs::read_to_string(&template_path).with_context(|| {
+            format!("Failed to read prompt template {}", template_path.display())
+        })?;
+        if let Some(unknown) = PLACEHOLDER
+            .captures_iter(&source)
+            .map(|captures| captures[1].to_string())
+            .find(|name| !PLACEHOLDERS.contains(&name.as_str()))
+        {
+            bail!(
+                "Unknown placeholder {{{{{unknown}}}}} in {}; expected one of {}",
+                template_path.display(),
+                PLACEHOLDERS.join(", ")
+            );
+        }
+        Ok(Some(PromptTemplate {
+            source,
+            nickname: repository.nickname.clone(),
+            path: repository.path.clone(),
+            branch: repository.branch_name.clone(),
+        }))
+    }
+
+    /// Fills in the placeholders for a change to `file`, or to several files when there is none,
+    /// passing every value but the already redacted `diff` through `redact`.
+    pub(crate) fn render(
+        &self,
+        file: Option<&Path>,
+        diff: &str,
+        mut redact: impl FnMut(String) -> String,
+    ) -> String {
+        let repo = Repository::open(&self.path).ok();
+        let branch = self.branch(repo.as_ref());
+        PLACEHOLDER
+            .replace_all(&self.source, |captures: &Captures| {
+                let value = match &captures[1] {
+                    "diff" => return diff.to_string(),
+                    "file" => file
+                        .map(|file| file.display().to_string())
+                        .unwrap_or_default(),
+                    "repository" => self.nickname.clone(),
+                    "branch" => branch.clone(),
+                    "recent_commits" => repo
+                        .as_ref()
+                        .map(|repo| recent_commits(repo, &branch))
+                        .unwrap_or_default(),
+                    "commit_types" => COMMIT_TYPES.join(", "),
+                    "language" => file.map(language).unwrap_or_default().to_string(),
+                    _ => return captures[0].to_string(),
+                };
+                redact(value)
+            })
+            .into_owned()
+    }
+
+    /// The branch ntangler commits to, which is the one HEAD is on; the configured one only
+    /// while HEAD is detached.
+    fn branch(&self, repo: Option<&Repository>) -> String {
+        repo.and_then(|repo| GitRepository::head_branch(repo).ok())
+            .unwrap_or_else(|| self.branch.clone())
+    }
+}
+
+/// The subjects of the latest commits on `branch`, newest first, one per line.
+fn recent_commits(repo: &Repository, branch: &str) -> String {
+    let subjects = || -> anyhow::Result<Vec<String>> {
+        let branch = repo.find_branch(branch, git2::BranchType::Local)?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(branch.get().peel_to_commit()?.id())?;
+        revwalk
+            .take(RECENT_COMMITS)
+            .map(|oid| {
+                let commit = repo.find_commit(oid?)?;
+                Ok(commit.summary().unwrap_or_default().to_string())
+            })
+            .collect()
+    };
+    subjects()
+        .map(|subjects| subjects.join("\n"))
+        .unwrap_or_default()
+}
+
+/// The language a file is written in, going by its name.
+fn language(file: &Path) -> &'static str {
+    let extension = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    match extension.to_ascii_lowercase().as_str() {
+        "rs" => "Rust",
+        "py" => "Python",
+        "js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" | "scss" => "CSS",
+        "md" | "markdown" => "Markdown",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "json" => "JSON",
+        _ => "plain text",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_render_prompt_template() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let first = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "feat: first\n\nbody",
+                &tree,
+                &[],
+            )
+            .unwrap();
+        let first = repo.find_commit(first).unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "fix: second",
+            &tree,
+            &[&first],
+        )
+        .unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        fs::write(
+            dir.path().join("prompt.md"),
+            "{{repository}}@{{ branch }}: {{file}} ({{language}})\n{{recent_commits}}\n{{diff}}",
+        )
+        .unwrap();
+        // Configured for a branch HEAD has since left.
+        let repository = NtangledRepository {
+            nickname: "tools".to_string(),
+            path: dir.path().to_path_buf(),
+            branch_name: "elsewhere".to_string(),
+            prompt_template: Some(PathBuf::from("prompt.md")),
+            ..Default::default()
+        };
+
+        let template = PromptTemplate::load(&repository).unwrap().unwrap();
+
+        assert_eq!(
+            template.render(Some(Path::new("src/lib.rs")), "+fn main() {}\n", |text| {
+                text
+            }),
+            format!(
+                "tools@{branch}: src/lib.rs (Rust)\nfix: second\nfeat: first\n+fn main() {{}}\n"
+            )
+        );
+        assert_eq!(
+            template.render(None, "+second\n", |text| text
+                .replace("second", "[REDACTED]")),
+            format!("tools@{branch}:  ()\nfix: [REDACTED]\nfeat: first\n+second\n")
+        );
+
+        fs::write(dir.path().join("prompt.md"), "{{diff}} {{ticket}}").unwrap();
+        let error = PromptTemplate::load(&repository).unwrap_err();
+        assert!(error.to_string().contains("{{ticket}}"));
+    }
+}
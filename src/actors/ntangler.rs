@@ -3,7 +3,7 @@ use std::time::Duration;
 
 use akton::prelude::*;
 use akton::prelude::Subscribable;
-use tracing::{debug, instrument, trace};
+use tracing::{debug, error, instrument, trace};
 
 use crate::actors::{LlmClient};
 use crate::actors::repositories::GitRepository;
@@ -44,7 +44,13 @@ impl Ntangler {
                         Some(broker.clone()),
                     )
                         .expect("Failed to create generator config");
-                    actor.state.generator = LlmClient::initialize(llm_config, &mut actor.akton)
+                    let repositories: Vec<NtangledRepository> = ntangler_config
+                        .repositories
+                        .iter()
+                        .cloned()
+                        .map(NtangledRepository::from)
+                        .collect();
+                    actor.state.generator = LlmClient::initialize(llm_config, &mut actor.akton, &repositories)
                         .await
                         .expect("Failed to initialize generator actor");
 
@@ -72,7 +78,14 @@ impl Ntangler {
                         })
                         .on_before_stop_async(|actor| {
                             let broker = actor.broker.clone();
+                            let git_repositories = actor.state.git_repositories.clone();
                             Box::pin(async move {
+                                // Repositories may still push on their way down, so they stop before the broker.
+                                for repository in git_repositories {
+                                    if let Err(e) = repository.suspend_actor().await {
+                                        error!(actor = repository.key, "Failed to stop repository: {e}");
+                                    }
+                                }
                                 broker
                                     .suspend_actor()
                                     .await
@@ -80,11 +93,10 @@ impl Ntangler {
                             })
                         });
 
-                    for repo in &ntangler_config.repositories {
+                    for ntangled_repository in repositories {
                         let akton = &mut actor.akton.clone();
-                        trace!(repo = ?repo, "Initializing a repository actor.");
+                        trace!(repo = ?ntangled_repository, "Initializing a repository actor.");
 
-                        let ntangled_repository: NtangledRepository = repo.clone().into();
                         let watcher = GitRepository::init(ntangled_repository, akton)
                             .await
                             .expect("Failed to start repository watcher");
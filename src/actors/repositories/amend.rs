@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use chrono::Utc;
+use anyhow::bail;
+use git2::{Commit, Oid, Repository, Signature, Tree};
+
+use super::{CommitLedger, GitRepository};
+
+impl GitRepository {
+    /// Returns HEAD when another commit of `target_file` should amend it instead: ntangler made
+    /// it for the same file within the amend window, and it hasn't been pushed.
+    pub(super) fn amend_candidate<'r>(
+        &self,
+        repo: &'r Repository,
+        target_file: &Path,
+    ) -> anyhow::Result<Option<Commit<'r>>> {
+        let window = self.repo_info.amend_window;
+        if window == 0 || self.repo_info.shadow {
+            return Ok(None);
+        }
+        let Some(head) = Self::head_commit(repo)? else {
+            return Ok(None);
+        };
+        if head.parent_count() > 1 {
+            return Ok(None);
+        }
+
+        let latest = CommitLedger::open(repo).latest()?;
+        if latest.as_ref().map(|(oid, file)| (*oid, file.as_path()))
+            != Some((head.id(), target_file))
+        {
+            return Ok(None);
+        }
+        // Amending keeps the author's time, so the window runs from the first save rather than
+        // sliding along with every amend.
+        let age = Utc::now().timestamp() - head.author().when().seconds();
+        if age > window as i64 {
+            return Ok(None);
+        }
+        if Self::first_pushed(repo, std::slice::from_ref(&head))?.is_some() {
+            return Ok(None);
+        }
+        Ok(Some(head))
+    }
+
+    /// The commit a diff was taken to amend, provided HEAD is still on it.
+    pub(super) fn amend_target<'r>(
+        &self,
+        repo: &'r Repository,
+        amending: Option<&str>,
+    ) -> anyhow::Result<Option<Commit<'r>>> {
+        let Some(hash) = amending else {
+            return Ok(None);
+        };
+        let amended = repo.find_commit(Oid::from_str(hash)?)?;
+        if Self::head_commit(repo)?.map(|head| head.id()) != Some(amended.id()) {
+            bail!("HEAD has moved on from {hash}, which the message was generated to amend");
+        }
+        Ok(Some(amended))
+    }
+
+    /// The tree the amended commit started from, so the regenerated message covers every save
+    /// folded into it.
+    pub(super) fn amend_base_tree<'r>(amended: &Commit<'r>) -> anyhow::Result<Option<Tree<'r>>> {
+        Ok(amended
+            .parents()
+            .next()
+            .map(|parent| parent.tree())
+            .transpose()?)
+    }
+
+    /// Replaces `amended` with a commit of `tree`, keeping its author and parents, and moves
+    /// HEAD along.
+    pub(super) fn write_amended_commit(
+        &self,
+        repo: &Repository,
+        amended: &Commit,
+        committer: &Signature,
+        message: &str,
+        tree: &Tree,
+    ) -> anyhow::Result<Oid> {
+        let parents: Vec<Commit> = amended.parents().collect();
+        let parents: Vec<&Commit> = parents.iter().collect();
+        let hash = self.write_commit(
+            repo,
+            None,
+            &amended.author(),
+            committer,
+            message,
+            tree,
+            &parents,
+        )?;
+
+        let summary = message.lines().next().unwrap_or_default();
+        let reflog_message = format!("commit (amend): {summary}");
+        let head = repo.find_reference("HEAD")?;
+        match head.symbolic_target() {
+            Some(target) => {
+                repo.reference_matching(target, hash, true, amended.id(), &reflog_message)?;
+            }
+            None => repo.set_head_detached(hash)?,
+        }
+        Ok(hash)
+    }
+}
@@ -0,0 +1,212 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+
+use anyhow::bail;
+use git2::{Commit, Config, Diff, DiffOptions, DiffStatsFormat, Repository};
+use tempfile::NamedTempFile;
+
+use crate::actors::scribe::Scribe;
+
+use super::GitRepository;
+
+/// The characters git picks from for `core.commentChar = auto`.
+const AUTO_COMMENT_CHARS: &str = "#;@!$%^&|:";
+
+/// A message written out for the user's editor, waiting for it to be opened.
+pub(crate) struct MessageEdit {
+    file: NamedTempFile,
+    editor: String,
+    workdir: PathBuf,
+    comment_char: char,
+}
+
+impl MessageEdit {
+    /// Opens the message in the editor, with the terminal handed over from the dashboard, and
+    /// returns what was saved without the comments; `None` when that is empty.
+    pub(crate) fn run(self) -> anyhow::Result<Option<String>> {
+        let status = Scribe::lend_terminal(|| {
+            editor_process(&self.editor, self.file.path())
+                .current_dir(&self.workdir)
+                .status()
+        })?;
+        if !status.success() {
+            bail!(
+                "{} exited with {status}; nothing was committed",
+                self.editor
+            );
+        }
+
+        let saved = strip_comments(&fs::read_to_string(self.file.path())?, self.comment_char);
+        Ok(Some(saved).filter(|saved| !saved.is_empty()))
+    }
+}
+
+/// Runs `editor` through the shell, as git does, so that editors configured with arguments work.
+#[cfg(unix)]
+fn editor_process(editor: &str, file: &Path) -> Command {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(format!("{editor} \"$@\""))
+        .arg(editor)
+        .arg(file);
+    command
+}
+
+#[cfg(not(unix))]
+fn editor_process(editor: &str, file: &Path) -> Command {
+    let mut command = Command::new("cmd");
+    command
+        .arg("/C")
+        .arg(format!("{editor} \"{}\"", file.display()));
+    command
+}
+
+impl GitRepository {
+    /// Writes `message`, followed by a summary of the diff of `paths` as comment lines, to a file
+    /// for the user's editor.
+    pub(super) fn prepare_edit(
+        &self,
+        repo: &Repository,
+        paths: &[&Path],
+        amending: Option<&Commit>,
+        message: &str,
+    ) -> anyhow::Result<MessageEdit> {
+        let config = repo.config()?;
+        let comment_char = comment_char(&config, message);
+        let stats = self
+            .pending_diff(repo, paths, amending)?
+            .stats()?
+            .to_buf(DiffStatsFormat::FULL, 72)?;
+        let stats = stats.as_str().unwrap_or_default();
+
+        let mut template = format!(
+            "{}\n\n\
+             {comment_char} Please edit the commit message for the changes below. Lines starting\n\
+             {comment_char} with '{comment_char}' will be ignored, and an empty message aborts the commit.\n\
+             {comment_char}\n",
+            message.trim_end()
+        );
+        for line in stats.lines() {
+            template.push_str(&format!("{comment_char} {line}\n"));
+        }
+
+        let mut file = tempfile::Builder::new()
+            .prefix("NTANGLER_EDITMSG")
+            .tempfile_in(repo.path())?;
+        file.write_all(template.as_bytes())?;
+        file.flush()?;
+
+        Ok(MessageEdit {
+            file,
+            editor: editor_command(&config, self.repo_info.editor_command.as_deref()),
+            workdir: repo.workdir().unwrap_or(repo.path()).to_path_buf(),
+            comment_char,
+        })
+    }
+
+    /// The changes to `paths` the next commit will record.
+    pub(super) fn pending_diff<'r>(
+        &self,
+        repo: &'r Repository,
+        paths: &[&Path],
+        amending: Option<&Commit>,
+    ) -> anyhow::Result<Diff<'r>> {
+        let mut diff_options = DiffOptions::new();
+        for path in paths {
+            diff_options.pathspec(path.as_os_str());
+        }
+        diff_options.include_untracked(true);
+        diff_options.recurse_untracked_dirs(true);
+        diff_options.show_untracked_content(true);
+        diff_options.disable_pathspec_match(true);
+
+        if self.repo_info.shadow {
+            let base = self.shadow_base(repo)?;
+            let base_tree = base.tree()?;
+            return Ok(repo.diff_tree_to_workdir(base_tree.as_ref(), Some(&mut diff_options))?);
+        }
+        let base_tree = match amending {
+            Some(amended) => Self::amend_base_tree(amended)?,
+            None => Self::head_commit(repo)?
+                .map(|head| head.tree())
+                .transpose()?,
+        };
+        Ok(repo.diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut diff_options))?)
+    }
+}
+
+/// Resolves the editor the way git does: `$GIT_EDITOR`, `core.editor`, `$VISUAL`, `$EDITOR`,
+/// then `vi`, unless the repository names one of its own.
+fn editor_command(config: &Config, configured: Option<&str>) -> String {
+    let non_empty = |editor: &String| !editor.trim().is_empty();
+    configured
+        .map(str::to_string)
+        .filter(non_empty)
+        .or_else(|| env::var("GIT_EDITOR").ok().filter(non_empty))
+        .or_else(|| config.get_string("core.editor").ok().filter(non_empty))
+        .or_else(|| env::var("VISUAL").ok().filter(non_empty))
+        .or_else(|| env::var("EDITOR").ok().filter(non_empty))
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Reads `core.commentChar`, choosing one that starts no line of `message` when it is `auto`.
+fn comment_char(config: &Config, message: &str) -> char {
+    match config.get_string("core.commentChar").as_deref() {
+        Ok("auto") => AUTO_COMMENT_CHARS
+            .chars()
+            .find(|c| !message.lines().any(|line| line.starts_with(*c)))
+            .unwrap_or('#'),
+        Ok(comment_char) => comment_char.chars().next().unwrap_or('#'),
+        Err(_) => '#',
+    }
+}
+
+/// Drops comment lines, trailing whitespace and repeated blank lines, as
+/// `git commit --cleanup=strip` does.
+fn strip_comments(text: &str, comment_char: char) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        if line.starts_with(comment_char) {
+            continue;
+        }
+        let line = line.trim_end();
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments() {
+        let text = "\nfeat: add route\n\n\n# Please edit\nbody  \n# 1 file changed\n\n";
+        assert_eq!(strip_comments(text, '#'), "feat: add route\n\nbody");
+        assert_eq!(strip_comments("; only comments\n;\n", ';'), "");
+        assert_eq!(strip_comments("fix: #12\n", ';'), "fix: #12");
+    }
+
+    #[test]
+    fn test_comment_char_from_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        assert_eq!(comment_char(&config, "feat: add"), '#');
+
+        config.set_str("core.commentChar", ";").unwrap();
+        assert_eq!(comment_char(&config, "feat: add"), ';');
+
+        config.set_str("core.commentChar", "auto").unwrap();
+        assert_eq!(comment_char(&config, "feat: add\n\n#12\n;13"), '@');
+    }
+}
@@ -0,0 +1,115 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::{env, fmt, fs};
+
+use git2::Repository;
+use tracing::*;
+
+/// Raised when a hook exits non-zero, carrying what it printed so the user can see why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HookRejected {
+    pub(crate) hook: String,
+    pub(crate) output: String,
+}
+
+impl fmt::Display for HookRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} hook rejected the commit: {}", self.hook, self.output.trim())
+    }
+}
+
+impl std::error::Error for HookRejected {}
+
+/// The client-side commit hooks of a repository, which libgit2 never runs on its own.
+pub(crate) struct GitHooks {
+    directory: PathBuf,
+    workdir: PathBuf,
+    index_file: PathBuf,
+    message_file: PathBuf,
+}
+
+impl GitHooks {
+    /// Finds the hooks directory from `core.hooksPath`, falling back to `.git/hooks`.
+    pub(crate) fn locate(repo: &Repository) -> anyhow::Result<GitHooks> {
+        let workdir = repo
+            .workdir()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| repo.path().to_path_buf());
+        let directory = match repo.config()?.get_path("core.hooksPath") {
+            Ok(hooks_path) => workdir.join(expand_home(&hooks_path)),
+            Err(_) => repo.path().join("hooks"),
+        };
+
+        Ok(GitHooks {
+            directory,
+            workdir,
+            index_file: repo.path().join("index"),
+            message_file: repo.path().join("COMMIT_EDITMSG"),
+        })
+    }
+
+    /// Runs `pre-commit` and then `commit-msg`, which may rewrite `message` in place.
+    pub(crate) fn before_commit(&self, message: &mut String) -> anyhow::Result<()> {
+        self.run("pre-commit", &[])?;
+
+        fs::write(&self.message_file, &message)?;
+        self.run("commit-msg", &[self.message_file.as_os_str()])?;
+        *message = fs::read_to_string(&self.message_file)?;
+        Ok(())
+    }
+
+    /// Runs `post-commit`, whose exit status git ignores as well.
+    pub(crate) fn after_commit(&self) {
+        if let Err(e) = self.run("post-commit", &[]) {
+            warn!("{e}");
+        }
+    }
+
+    fn run(&self, name: &str, args: &[&OsStr]) -> anyhow::Result<()> {
+        let hook = self.directory.join(name);
+        if !is_executable(&hook) {
+            return Ok(());
+        }
+
+        trace!(hook = name, "Running git hook");
+        let output = Command::new(&hook)
+            .args(args)
+            .current_dir(&self.workdir)
+            .env("GIT_INDEX_FILE", &self.index_file)
+            .stdin(Stdio::null())
+            .output()?;
+
+        if output.status.success() {
+            return Ok(());
+        }
+        let mut hook_output = String::from_utf8_lossy(&output.stdout).to_string();
+        hook_output.push_str(&String::from_utf8_lossy(&output.stderr));
+        Err(HookRejected {
+            hook: name.to_string(),
+            output: hook_output.trim().to_string(),
+        }
+        .into())
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var("HOME")) {
+        (Ok(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
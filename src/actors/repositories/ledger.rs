@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use git2::{Commit, Oid, Repository};
+
+/// The commits ntangler has made in a repository, kept in `.git/ntangler/commits` so they can be
+/// told apart from manual commits later on.
+pub(crate) struct CommitLedger {
+    path: PathBuf,
+}
+
+impl CommitLedger {
+    pub(crate) fn open(repo: &Repository) -> CommitLedger {
+        CommitLedger {
+            path: repo.path().join("ntangler").join("commits"),
+        }
+    }
+
+    /// Records that ntangler made `oid` for `target_file`.
+    pub(crate) fn record(&self, oid: Oid, target_file: &Path) -> anyhow::Result<()> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut ledger = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(ledger, "{oid}\t{}", target_file.display())?;
+        Ok(())
+    }
+
+    /// Maps every recorded commit to the file it was made for.
+    pub(crate) fn entries(&self) -> anyhow::Result<HashMap<Oid, PathBuf>> {
+        Ok(self.read()?.lines().filter_map(parse_entry).collect())
+    }
+
+    /// The commit ntangler made most recently, and the file it was made for.
+    pub(crate) fn latest(&self) -> anyhow::Result<Option<(Oid, PathBuf)>> {
+        Ok(self.read()?.lines().rev().find_map(parse_entry))
+    }
+
+    /// Drops `oid` from the ledger once it has been undone, so the commit before it counts as the
+    /// latest again.
+    pub(crate) fn forget(&self, oid: Oid) -> anyhow::Result<()> {
+        let contents = self.read()?;
+        let kept: String = contents
+            .lines()
+            .filter(|line| parse_entry(line).map(|(entry, _)| entry) != Some(oid))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        if kept == contents {
+            return Ok(());
+        }
+        Ok(fs::write(&self.path, kept)?)
+    }
+
+    fn read(&self) -> anyhow::Result<String> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(contents),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Whether ntangler made `commit`, going by the ledger or its `Generated-by` trailer.
+    pub(crate) fn is_ntangler_commit(entries: &HashMap<Oid, PathBuf>, commit: &Commit) -> bool {
+        entries.contains_key(&commit.id())
+            || commit
+                .message()
+                .unwrap_or_default()
+                .lines()
+                .any(|line| line.starts_with("Generated-by: ntangler"))
+    }
+}
+
+fn parse_entry(line: &str) -> Option<(Oid, PathBuf)> {
+    let (oid, path) = line.split_once('\t')?;
+    Some((Oid::from_str(oid).ok()?, PathBuf::from(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read_entries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let ledger = CommitLedger::open(&repo);
+        assert!(ledger.entries().unwrap().is_empty());
+
+        let oid = Oid::hash_object(git2::ObjectType::Blob, b"a").unwrap();
+        ledger.record(oid, Path::new("src/a.rs")).unwrap();
+        let latest = Oid::hash_object(git2::ObjectType::Blob, b"b").unwrap();
+        ledger.record(latest, Path::new("src/b.rs")).unwrap();
+
+        assert_eq!(
+            ledger.entries().unwrap().get(&oid),
+            Some(&PathBuf::from("src/a.rs"))
+        );
+        assert_eq!(
+            ledger.latest().unwrap(),
+            Some((latest, PathBuf::from("src/b.rs")))
+        );
+
+        ledger.forget(latest).unwrap();
+        assert_eq!(
+            ledger.latest().unwrap(),
+            Some((oid, PathBuf::from("src/a.rs")))
+        );
+        assert!(!ledger.entries().unwrap().contains_key(&latest));
+    }
+}
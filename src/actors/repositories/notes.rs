@@ -0,0 +1,30 @@
+use git2::{Oid, Repository};
+
+use crate::models::GenerationMetadata;
+
+use super::GitRepository;
+
+/// The notes ref that holds how each ntangler commit message was generated.
+pub(crate) const NOTES_REF: &str = "refs/notes/ntangler";
+
+impl GitRepository {
+    /// Records `metadata` as a note on `hash`, replacing any note already there.
+    pub(crate) fn attach_note(
+        &self,
+        hash: Oid,
+        metadata: &GenerationMetadata,
+    ) -> anyhow::Result<()> {
+        let repo = Repository::open(&self.repo_info.path)?;
+        let (_, committer) = self.identities(&repo)?;
+        let signature = committer.to_signature()?;
+        repo.note(
+            &signature,
+            &signature,
+            Some(NOTES_REF),
+            hash,
+            &metadata.to_string(),
+            true,
+        )?;
+        Ok(())
+    }
+}
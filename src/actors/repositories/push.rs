@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+
+use anyhow::bail;
+use chrono::Utc;
+use git2::{Config, Cred, CredentialType, PushOptions, RemoteCallbacks, Repository};
+use tracing::*;
+
+use crate::messages::{PushCompleted, PushRejected};
+use crate::models::TimeStamp;
+
+use super::GitRepository;
+
+type PushResult = Result<PushCompleted, PushRejected>;
+
+/// A push the repository actor decided on, to run on tokio's blocking pool so the network round
+/// trip doesn't hold up the executor.
+#[derive(Debug)]
+pub(crate) struct PendingPush {
+    pub(crate) path: PathBuf,
+    pub(crate) nickname: String,
+    pub(crate) remote: String,
+    /// The configured branch, or empty to push the one HEAD is on.
+    pub(crate) branch: String,
+    /// How many waiting commits the push accounts for.
+    pub(crate) commits: usize,
+}
+
+impl PendingPush {
+    pub(crate) async fn run(self) -> PushResult {
+        let PendingPush {
+            path,
+            nickname,
+            remote,
+            branch,
+            commits,
+        } = self;
+        let (push_remote, push_branch_name) = (remote.clone(), branch.clone());
+        let pushed = tokio::task::spawn_blocking(move || -> anyhow::Result<String> {
+            let repo = Repository::open(path)?;
+            let branch = if push_branch_name.is_empty() {
+                GitRepository::head_branch(&repo)?
+            } else {
+                push_branch_name
+            };
+            push_branch(&repo, &push_remote, &branch)?;
+            Ok(branch)
+        })
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|pushed| pushed);
+
+        match pushed {
+            Ok(branch) => {
+                info!(repo = nickname, remote, branch, "Pushed {commits} commits");
+                let when = TimeStamp::new(Utc::now());
+                Ok(PushCompleted::new(when, nickname, remote, branch, commits))
+            }
+            Err(e) => {
+                error!(repo = nickname, remote, branch, "Failed to push: {e}");
+                Err(PushRejected::new(nickname, remote, branch, e.to_string()))
+            }
+        }
+    }
+}
+
+/// Pushes `branch` to the same branch on `remote_name`.
+///
+/// Fails when the remote cannot be reached or refuses the update, for example on a non-fast-forward.
+pub(crate) fn push_branch(repo: &Repository, remote_name: &str, branch: &str) -> anyhow::Result<()> {
+    let mut remote = repo.find_remote(remote_name)?;
+    let config = repo.config()?;
+    let mut credentials = CredentialAttempts::default();
+    let mut rejections = Vec::new();
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(|url, username_from_url, allowed_types| {
+        credentials.next(&config, url, username_from_url, allowed_types)
+    });
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(status) = status {
+            rejections.push(format!("{refname} ({status})"));
+        }
+        Ok(())
+    });
+    let mut push_options = PushOptions::new();
+    push_options.remote_callbacks(callbacks);
+
+    let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+    trace!(remote = remote_name, refspec, "Pushing");
+    remote.push(&[refspec.as_str()], Some(&mut push_options))?;
+    drop(push_options);
+
+    if !rejections.is_empty() {
+        bail!("{remote_name} rejected {}", rejections.join(", "));
+    }
+    Ok(())
+}
+
+/// Offers ssh-agent, the credential helpers and default credentials once each, since libgit2 keeps
+/// asking for as long as the callback keeps answering.
+#[derive(Default)]
+struct CredentialAttempts {
+    username: bool,
+    ssh_agent: bool,
+    credential_helper: bool,
+    default: bool,
+}
+
+impl CredentialAttempts {
+    fn next(
+        &mut self,
+        config: &Config,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed_types: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        let username = username_from_url.unwrap_or("git");
+        if allowed_types.contains(CredentialType::USERNAME) && !self.username {
+            self.username = true;
+            return Cred::username(username);
+        }
+        if allowed_types.contains(CredentialType::SSH_KEY) && !self.ssh_agent {
+            self.ssh_agent = true;
+            return Cred::ssh_key_from_agent(username);
+        }
+        if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) && !self.credential_helper {
+            self.credential_helper = true;
+            return Cred::credential_helper(config, url, username_from_url);
+        }
+        if allowed_types.contains(CredentialType::DEFAULT) && !self.default {
+            self.default = true;
+            return Cred::default();
+        }
+        Err(git2::Error::from_str(&format!("No accepted credentials for {url}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_credential_is_offered_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config::open(&dir.path().join("config")).unwrap();
+        let mut credentials = CredentialAttempts::default();
+        let url = "ssh://git@example.com/repo.git";
+
+        assert!(credentials
+            .next(&config, url, Some("git"), CredentialType::SSH_KEY)
+            .is_ok());
+        assert!(credentials
+            .next(&config, url, Some("git"), CredentialType::SSH_KEY)
+            .is_err());
+    }
+}
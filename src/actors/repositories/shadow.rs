@@ -0,0 +1,335 @@
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+use chrono::Utc;
+use git2::{
+    Commit, Delta, DiffOptions, Index, IndexEntry, IndexTime, Oid, Repository, Signature, Sort,
+    Tree,
+};
+use tracing::*;
+
+use crate::messages::ShadowHistoryArchived;
+use crate::models::{GenerationMetadata, OperationInProgress};
+
+use super::{GitRepository, NothingToCommit};
+
+/// The private ref that shadow-ref mode records `branch`'s micro-commits on.
+pub(crate) fn shadow_ref_name(branch: &str) -> String {
+    format!("refs/ntangler/{branch}")
+}
+
+/// Where shadow history the branch moved past is kept. It lives outside `refs/ntangler/`, where
+/// it would clash with the live shadow ref of the same branch.
+pub(crate) fn shadow_archive_ref_name(branch: &str, timestamp: i64) -> String {
+    format!("refs/ntangler-archive/{branch}/{timestamp}")
+}
+
+/// The commit the next shadow commit builds on.
+pub(super) struct ShadowBase<'r> {
+    pub(super) branch: String,
+    pub(super) reference: String,
+    pub(super) commit: Option<Commit<'r>>,
+    /// The branch has moved past the shadow ref, so shadow history starts over from the branch
+    /// once the old one is archived.
+    pub(super) stale: bool,
+}
+
+impl ShadowBase<'_> {
+    pub(super) fn tree(&self) -> anyhow::Result<Option<Tree<'_>>> {
+        Ok(self.commit.as_ref().map(Commit::tree).transpose()?)
+    }
+}
+
+/// What `ntangler promote` put onto the branch.
+#[derive(Debug)]
+pub(crate) struct Promotion {
+    pub(crate) branch: String,
+    pub(crate) commits: usize,
+    pub(crate) squashed: bool,
+    pub(crate) hash: Oid,
+}
+
+impl fmt::Display for Promotion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.commits == 1 { "" } else { "s" };
+        if self.squashed {
+            write!(f, "Squashed {} commit{plural} onto {} as {}", self.commits, self.branch, self.hash)
+        } else {
+            write!(f, "Promoted {} commit{plural} onto {} at {}", self.commits, self.branch, self.hash)
+        }
+    }
+}
+
+impl GitRepository {
+    /// Resolves the shadow ref for the current branch and the commit it continues from.
+    pub(super) fn shadow_base<'r>(&self, repo: &'r Repository) -> anyhow::Result<ShadowBase<'r>> {
+        let branch = self.branch(repo)?;
+        let reference = shadow_ref_name(&branch);
+        let branch_tip = repo.refname_to_id(&format!("refs/heads/{branch}")).ok();
+        let shadow_tip = repo.refname_to_id(&reference).ok();
+
+        let (base, stale) = match (shadow_tip, branch_tip) {
+            (Some(shadow), Some(branch))
+                if shadow == branch || repo.graph_descendant_of(shadow, branch)? =>
+            {
+                (Some(shadow), false)
+            }
+            (Some(_), Some(branch)) => (Some(branch), true),
+            (Some(shadow), None) => (Some(shadow), false),
+            (None, branch) => (branch, false),
+        };
+
+        Ok(ShadowBase {
+            branch,
+            reference,
+            commit: base.map(|oid| repo.find_commit(oid)).transpose()?,
+            stale,
+        })
+    }
+
+    /// Moves shadow history the branch has moved past to an archive ref, so recording starts over
+    /// from the branch without leaving the old commits unreachable.
+    pub(super) fn archive_stale_shadow_ref(
+        &self,
+        repo: &Repository,
+    ) -> anyhow::Result<Option<ShadowHistoryArchived>> {
+        let base = self.shadow_base(repo)?;
+        if !base.stale {
+            return Ok(None);
+        }
+        let archive_ref = shadow_archive_ref_name(&base.branch, Utc::now().timestamp());
+        let reflog_message = format!("ntangler: {} moved on", base.branch);
+        repo.find_reference(&base.reference)?
+            .rename(&archive_ref, false, &reflog_message)?;
+        warn!(reference = base.reference, archive_ref, "{} moved on, restarting shadow history from it", base.branch);
+        Ok(Some(ShadowHistoryArchived::new(
+            self.repo_info.nickname.clone(),
+            base.branch,
+            archive_ref,
+        )))
+    }
+
+    /// Lists the files whose working tree content differs from the shadow ref.
+    pub(super) fn shadow_modified_files(&self, repo: &Repository) -> anyhow::Result<Vec<String>> {
+        let base = self.shadow_base(repo)?;
+        let mut diff_options = DiffOptions::new();
+        diff_options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_typechange(true);
+        let diff = repo.diff_tree_to_workdir(base.tree()?.as_ref(), Some(&mut diff_options))?;
+
+        Ok(diff
+            .deltas()
+            .filter(|delta| delta.status() != Delta::Deleted)
+            .filter_map(|delta| delta.new_file().path())
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Commits the working tree content of `paths` onto the shadow ref without touching the
+    /// index, HEAD or the branch.
+    pub(super) fn commit_to_shadow_ref(
+        &self,
+        repo: &Repository,
+        paths: &[&Path],
+        author: &Signature,
+        committer: &Signature,
+        message: &str,
+    ) -> anyhow::Result<Oid> {
+        // Polls archive stale history and report it; this only catches a branch that moved since.
+        self.archive_stale_shadow_ref(repo)?;
+        let base = self.shadow_base(repo)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow!("Shadow refs need a working tree"))?;
+
+        // A detached index keeps the user's staging area out of it.
+        let mut index = Index::new()?;
+        if let Some(tree) = base.tree()? {
+            index.read_tree(&tree)?;
+        }
+        for path in paths {
+            trace!(file = ?path, "Shadow index add");
+            index.add(&working_tree_entry(repo, &workdir.join(path), path)?)?;
+        }
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        if base.commit.as_ref().is_some_and(|commit| commit.tree_id() == tree.id()) {
+            return Err(NothingToCommit.into());
+        }
+        let parents: Vec<&Commit> = base.commit.iter().collect();
+        self.write_commit(repo, Some(&base.reference), author, committer, message, &tree, &parents)
+    }
+
+    /// Moves the shadow history onto the branch, either commit by commit or squashed into one.
+    pub(crate) fn promote(&self, squash: bool) -> anyhow::Result<Promotion> {
+        let repo = Repository::open(&self.repo_info.path)?;
+        if let Some(operation) = OperationInProgress::from_state(repo.state()) {
+            bail!("Cannot promote while {operation}");
+        }
+
+        let base = self.shadow_base(&repo)?;
+        if base.stale {
+            bail!("{} has moved on since {} was recorded", base.branch, base.reference);
+        }
+        let Ok(shadow_tip) = repo.refname_to_id(&base.reference) else {
+            bail!("Nothing has been recorded in {}", base.reference);
+        };
+        let branch_ref = format!("refs/heads/{}", base.branch);
+        let branch_tip = repo.refname_to_id(&branch_ref).ok();
+        if branch_tip == Some(shadow_tip) {
+            bail!("{} already contains everything in {}", base.branch, base.reference);
+        }
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk.push(shadow_tip)?;
+        if let Some(branch_tip) = branch_tip {
+            revwalk.hide(branch_tip)?;
+        }
+        let commits = revwalk.collect::<Result<Vec<Oid>, _>>()?;
+
+        let branch_commit = branch_tip.map(|oid| repo.find_commit(oid)).transpose()?;
+        let hash = if squash {
+            let hash = self.squash_shadow_commits(&repo, &branch_ref, &commits, branch_commit.as_ref())?;
+            // Continue shadow history from the squashed commit rather than starting over.
+            repo.reference(&base.reference, hash, true, "ntangler: squash")?;
+            hash
+        } else {
+            let reflog_message = format!("ntangler: promote {}", base.reference);
+            repo.reference(&branch_ref, shadow_tip, true, &reflog_message)?;
+            shadow_tip
+        };
+
+        // The working tree already holds the promoted content, so only the index needs to follow,
+        // except where the user has staged something of their own.
+        let head_target = repo.find_reference("HEAD")?.symbolic_target().map(str::to_string);
+        if head_target.as_deref() == Some(branch_ref.as_str()) {
+            let promoted = repo.find_commit(hash)?;
+            let old_tree = branch_commit.as_ref().map(Commit::tree).transpose()?;
+            let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&promoted.tree()?), None)?;
+            let index = repo.index()?;
+            let paths: Vec<PathBuf> = diff
+                .deltas()
+                .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+                .filter(|path| !is_staged(&index, old_tree.as_ref(), path))
+                .map(Path::to_path_buf)
+                .collect();
+            if !paths.is_empty() {
+                repo.reset_default(Some(promoted.as_object()), paths.iter().map(PathBuf::as_path))?;
+            }
+        }
+
+        Ok(Promotion {
+            branch: base.branch,
+            commits: commits.len(),
+            squashed: squash,
+            hash,
+        })
+    }
+
+    fn squash_shadow_commits(
+        &self,
+        repo: &Repository,
+        branch_ref: &str,
+        commits: &[Oid],
+        branch_commit: Option<&Commit>,
+    ) -> anyhow::Result<Oid> {
+        let shadow_commits = commits
+            .iter()
+            .map(|oid| repo.find_commit(*oid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some(tip) = shadow_commits.last() else {
+            bail!("Nothing to squash");
+        };
+
+        let message = match shadow_commits.as_slice() {
+            [only] => only.message().unwrap_or_default().to_string(),
+            _ => squash_message(&shadow_commits),
+        };
+        let (author, committer) = self.identities(repo)?;
+        let parents: Vec<&Commit> = branch_commit.into_iter().collect();
+        let hash = self.write_commit(
+            repo,
+            Some(branch_ref),
+            &author.to_signature()?,
+            &committer.to_signature()?,
+            &message,
+            &tip.tree()?,
+            &parents,
+        )?;
+        if let Err(e) = self.attach_note(hash, &GenerationMetadata::heuristic(0)) {
+            warn!(branch = branch_ref, "Failed to attach generation note: {e}");
+        }
+        Ok(hash)
+    }
+}
+
+fn squash_message(commits: &[Commit]) -> String {
+    let summaries: Vec<String> = commits
+        .iter()
+        .map(|commit| format!("- {}", commit.summary().unwrap_or_default()))
+        .collect();
+    format!(
+        "chore: squash {} ntangler commits\n\n{}",
+        commits.len(),
+        summaries.join("\n")
+    )
+}
+
+/// Whether the index holds something for `path` other than what `tree` has.
+fn is_staged(index: &Index, tree: Option<&Tree>, path: &Path) -> bool {
+    let staged = index.get_path(path, 0).map(|entry| entry.id);
+    let committed = tree
+        .and_then(|tree| tree.get_path(path).ok())
+        .map(|entry| entry.id());
+    staged != committed
+}
+
+/// Writes a working tree file as a blob and describes it as an index entry at `path`.
+fn working_tree_entry(repo: &Repository, file: &Path, path: &Path) -> anyhow::Result<IndexEntry> {
+    let metadata = fs::symlink_metadata(file)?;
+    let (mode, id) = if metadata.file_type().is_symlink() {
+        let target = fs::read_link(file)?;
+        (0o120000, repo.blob(target.to_string_lossy().as_bytes())?)
+    } else {
+        // Written through git's clean filters (autocrlf, `.gitattributes`), as `git add` would.
+        let mut writer = repo.blob_writer(Some(path))?;
+        writer.write_all(&fs::read(file)?)?;
+        (file_mode(&metadata), writer.commit()?)
+    };
+
+    Ok(IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: metadata.len() as u32,
+        id,
+        flags: 0,
+        flags_extended: 0,
+        path: path.to_string_lossy().replace('\\', "/").into_bytes(),
+    })
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    if metadata.permissions().mode() & 0o111 != 0 {
+        0o100755
+    } else {
+        0o100644
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> u32 {
+    0o100644
+}
@@ -0,0 +1,254 @@
+use std::env;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, bail, Context};
+use git2::{Config, Signature};
+use tempfile::NamedTempFile;
+use tracing::*;
+
+use crate::models::config::SigningConfig;
+
+/// The signature formats git understands for `gpg.format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SigningFormat {
+    OpenPgp,
+    X509,
+    Ssh,
+}
+
+impl SigningFormat {
+    fn parse(format: &str) -> anyhow::Result<SigningFormat> {
+        match format.to_lowercase().as_str() {
+            "openpgp" => Ok(SigningFormat::OpenPgp),
+            "x509" => Ok(SigningFormat::X509),
+            "ssh" => Ok(SigningFormat::Ssh),
+            other => bail!("Unsupported gpg.format '{other}'"),
+        }
+    }
+
+    fn default_program(&self) -> &'static str {
+        match self {
+            SigningFormat::OpenPgp => "gpg",
+            SigningFormat::X509 => "gpgsm",
+            SigningFormat::Ssh => "ssh-keygen",
+        }
+    }
+
+    fn program_key(&self) -> &'static str {
+        match self {
+            SigningFormat::OpenPgp => "gpg.openpgp.program",
+            SigningFormat::X509 => "gpg.x509.program",
+            SigningFormat::Ssh => "gpg.ssh.program",
+        }
+    }
+}
+
+/// Signs commit buffers the way `git commit -S` would, by shelling out to gpg, gpgsm or ssh-keygen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CommitSigner {
+    pub(crate) format: SigningFormat,
+    pub(crate) program: String,
+    pub(crate) key: Option<String>,
+}
+
+impl CommitSigner {
+    /// Resolves the signing settings from git config with the repository's overrides applied.
+    ///
+    /// Returns `None` when commits should not be signed.
+    pub(crate) fn from_config(
+        config: &Config,
+        overrides: &SigningConfig,
+    ) -> anyhow::Result<Option<CommitSigner>> {
+        let enabled = overrides
+            .enabled
+            .or_else(|| config.get_bool("commit.gpgsign").ok())
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+
+        let format = match overrides
+            .format
+            .clone()
+            .or_else(|| config.get_string("gpg.format").ok())
+        {
+            Some(format) => SigningFormat::parse(&format)?,
+            None => SigningFormat::OpenPgp,
+        };
+
+        // `gpg.program` predates the per-format keys and still applies to OpenPGP.
+        let program = config
+            .get_string(format.program_key())
+            .ok()
+            .or_else(|| match format {
+                SigningFormat::OpenPgp => config.get_string("gpg.program").ok(),
+                _ => None,
+            })
+            .unwrap_or_else(|| format.default_program().to_string());
+
+        let key = overrides
+            .key
+            .clone()
+            .or_else(|| config.get_string("user.signingkey").ok())
+            .filter(|key| !key.is_empty());
+
+        Ok(Some(CommitSigner {
+            format,
+            program,
+            key,
+        }))
+    }
+
+    /// Produces a detached signature for the commit buffer.
+    pub(crate) fn sign(&self, buffer: &str, committer: &Signature) -> anyhow::Result<String> {
+        match self.format {
+            SigningFormat::OpenPgp | SigningFormat::X509 => self.sign_with_gpg(buffer, committer),
+            SigningFormat::Ssh => self.sign_with_ssh(buffer),
+        }
+    }
+
+    fn sign_with_gpg(&self, buffer: &str, committer: &Signature) -> anyhow::Result<String> {
+        // Like git, default to the committer identity when no key is configured.
+        let key = match &self.key {
+            Some(key) => key.clone(),
+            None => committer.to_string(),
+        };
+
+        let mut command = Command::new(&self.program);
+        command.args(["--status-fd=2", "-bsau", &key]);
+        let output = self.run(command, buffer)?;
+
+        let status = String::from_utf8_lossy(&output.stderr);
+        if !status.contains("[GNUPG:] SIG_CREATED ") {
+            bail!("{} did not create a signature: {}", self.program, status.trim());
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    fn sign_with_ssh(&self, buffer: &str) -> anyhow::Result<String> {
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| anyhow!("user.signingkey must be set to sign commits with ssh"))?;
+
+        let mut command = Command::new(&self.program);
+        command.args(["-Y", "sign", "-n", "git", "-f"]);
+
+        // A literal public key means the private half lives in ssh-agent.
+        let _literal_key_file;
+        if let Some(public_key) = literal_ssh_key(key) {
+            let mut key_file = NamedTempFile::new()?;
+            writeln!(key_file, "{public_key}")?;
+            command.arg(key_file.path()).arg("-U");
+            _literal_key_file = key_file;
+        } else {
+            command.arg(expand_home(key));
+        }
+
+        let output = self.run(command, buffer)?;
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    fn run(&self, mut command: Command, buffer: &str) -> anyhow::Result<std::process::Output> {
+        trace!(program = self.program, "Signing commit");
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", self.program))?;
+
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(buffer.as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "{} failed to sign the commit: {}",
+                self.program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output)
+    }
+}
+
+fn literal_ssh_key(key: &str) -> Option<&str> {
+    key.strip_prefix("key::")
+        .or_else(|| key.starts_with("ssh-").then_some(key))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with(entries: &[(&str, &str)]) -> (tempfile::TempDir, Config) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::open(&dir.path().join("config")).unwrap();
+        for (key, value) in entries {
+            config.set_str(key, value).unwrap();
+        }
+        (dir, config)
+    }
+
+    #[test]
+    fn test_signing_disabled_by_default() {
+        let (_dir, config) = config_with(&[]);
+        let signer = CommitSigner::from_config(&config, &SigningConfig::default()).unwrap();
+        assert_eq!(signer, None);
+    }
+
+    #[test]
+    fn test_signing_reads_git_config() {
+        let (_dir, config) = config_with(&[
+            ("commit.gpgsign", "true"),
+            ("gpg.format", "ssh"),
+            ("user.signingkey", "~/.ssh/id_ed25519"),
+        ]);
+        let signer = CommitSigner::from_config(&config, &SigningConfig::default())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(signer.format, SigningFormat::Ssh);
+        assert_eq!(signer.program, "ssh-keygen");
+        assert_eq!(signer.key.as_deref(), Some("~/.ssh/id_ed25519"));
+    }
+
+    #[test]
+    fn test_repository_overrides_win() {
+        let (_dir, config) = config_with(&[
+            ("commit.gpgsign", "false"),
+            ("gpg.program", "/opt/gpg2"),
+            ("user.signingkey", "ABCDEF"),
+        ]);
+        let overrides = SigningConfig {
+            enabled: Some(true),
+            key: Some("123456".to_string()),
+            ..Default::default()
+        };
+        let signer = CommitSigner::from_config(&config, &overrides).unwrap().unwrap();
+
+        assert_eq!(signer.format, SigningFormat::OpenPgp);
+        assert_eq!(signer.program, "/opt/gpg2");
+        assert_eq!(signer.key.as_deref(), Some("123456"));
+    }
+
+    #[test]
+    fn test_literal_ssh_key() {
+        assert_eq!(literal_ssh_key("key::ssh-ed25519 AAAA"), Some("ssh-ed25519 AAAA"));
+        assert_eq!(literal_ssh_key("ssh-ed25519 AAAA"), Some("ssh-ed25519 AAAA"));
+        assert_eq!(literal_ssh_key("/home/me/.ssh/id_ed25519"), None);
+    }
+}
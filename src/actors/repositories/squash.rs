@@ -0,0 +1,228 @@
+use anyhow::{anyhow, bail};
+use git2::{Commit, DiffFormat, Oid, Repository};
+
+use crate::models::{CommitMessage, OperationInProgress};
+
+use super::{CommitLedger, GitRepository};
+
+/// A run of ntangler commits that become one commit.
+#[derive(Debug)]
+pub(crate) struct SquashGroup {
+    pub(crate) commits: Vec<Oid>,
+    /// The messages of the commits being replaced, oldest first.
+    pub(crate) messages: Vec<String>,
+    /// Everything the group changes, as one patch.
+    pub(crate) diff: String,
+    tree: Oid,
+}
+
+/// The ntangler commits on top of a branch that `ntangler squash` replaces.
+#[derive(Debug)]
+pub(crate) struct SquashPlan {
+    branch_ref: String,
+    head: Oid,
+    base: Option<Oid>,
+    pub(crate) groups: Vec<SquashGroup>,
+}
+
+impl SquashPlan {
+    pub(crate) fn commit_count(&self) -> usize {
+        self.groups.iter().map(|group| group.commits.len()).sum()
+    }
+}
+
+impl GitRepository {
+    /// Collects the consecutive ntangler commits at the tip of the branch, back to `since` when
+    /// given, grouping neighbours that share a scope when `by_scope` is set.
+    ///
+    /// Refuses when any of them has reached a remote.
+    pub(crate) fn plan_squash(
+        &self,
+        since: Option<&str>,
+        by_scope: bool,
+    ) -> anyhow::Result<SquashPlan> {
+        let repo = Repository::open(&self.repo_info.path)?;
+        if let Some(operation) = OperationInProgress::from_state(repo.state()) {
+            bail!("Cannot squash while {operation}");
+        }
+        let branch_ref = format!("refs/heads/{}", self.branch(&repo)?);
+        let head = repo.refname_to_id(&branch_ref)?;
+        let since = since
+            .map(|since| {
+                repo.revparse_single(since)?
+                    .peel_to_commit()
+                    .map(|commit| commit.id())
+            })
+            .transpose()?;
+
+        let entries = CommitLedger::open(&repo).entries()?;
+        let mut commits = Vec::new();
+        let mut commit = repo.find_commit(head)?;
+        let base = loop {
+            if Some(commit.id()) == since {
+                break Some(commit.id());
+            }
+            if commit.parent_count() > 1 || !CommitLedger::is_ntangler_commit(&entries, &commit) {
+                if since.is_some() {
+                    bail!("{} was not made by ntangler", short_id(&commit));
+                }
+                break Some(commit.id());
+            }
+            let parent = commit.parents().next();
+            commits.push(commit);
+            match parent {
+                Some(parent) => commit = parent,
+                None if since.is_some() => {
+                    bail!("The branch has no commit matching the given point")
+                }
+                None => break None,
+            }
+        };
+        commits.reverse();
+
+        if commits.len() < 2 {
+            bail!("There are fewer than two ntangler commits to squash");
+        }
+        if let Some(pushed) = Self::first_pushed(&repo, &commits)? {
+            bail!("{} has already been pushed", short_id(pushed));
+        }
+
+        let mut groups: Vec<Vec<Commit>> = Vec::new();
+        for commit in commits {
+            match groups.last_mut() {
+                Some(group) if by_scope && scope(group[0].summary()) == scope(commit.summary()) => {
+                    group.push(commit)
+                }
+                _ => groups.push(vec![commit]),
+            }
+        }
+
+        let mut parent_tree = base.map(|oid| repo.find_commit(oid)?.tree()).transpose()?;
+        let mut squash_groups = Vec::new();
+        for group in groups {
+            let tree = group.last().expect("groups are never empty").tree()?;
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            let mut patch = Vec::new();
+            diff.print(DiffFormat::Patch, |_, _, line| {
+                if matches!(line.origin(), '+' | '-' | ' ') {
+                    patch.push(line.origin() as u8);
+                }
+                patch.extend_from_slice(line.content());
+                true
+            })?;
+
+            squash_groups.push(SquashGroup {
+                commits: group.iter().map(Commit::id).collect(),
+                messages: group
+                    .iter()
+                    .map(|commit| commit.message().unwrap_or_default().trim().to_string())
+                    .collect(),
+                diff: String::from_utf8_lossy(&patch).to_string(),
+                tree: tree.id(),
+            });
+            parent_tree = Some(tree);
+        }
+
+        Ok(SquashPlan {
+            branch_ref,
+            head,
+            base,
+            groups: squash_groups,
+        })
+    }
+
+    /// Replaces the planned commits with one commit per group, as long as the branch has not
+    /// moved since the plan was made.
+    pub(crate) fn apply_squash(
+        &self,
+        plan: &SquashPlan,
+        commit_messages: &[CommitMessage],
+    ) -> anyhow::Result<Vec<Oid>> {
+        if commit_messages.len() != plan.groups.len() {
+            bail!(
+                "Expected {} commit messages, got {}",
+                plan.groups.len(),
+                commit_messages.len()
+            );
+        }
+        let repo = Repository::open(&self.repo_info.path)?;
+        let (author, committer) = self.identities(&repo)?;
+        let ledger = CommitLedger::open(&repo);
+
+        let mut parent = plan.base.map(|oid| repo.find_commit(oid)).transpose()?;
+        let mut hashes = Vec::new();
+        for (group, commit_message) in plan.groups.iter().zip(commit_messages) {
+            let message = self
+                .with_attribution(commit_message, &author, &committer)
+                .to_string();
+            let tree = repo.find_tree(group.tree)?;
+            let parents: Vec<&Commit> = parent.iter().collect();
+            let hash = self.write_commit(
+                &repo,
+                None,
+                &author.to_signature()?,
+                &committer.to_signature()?,
+                &message,
+                &tree,
+                &parents,
+            )?;
+            ledger.record(hash, std::path::Path::new("."))?;
+            hashes.push(hash);
+            parent = Some(repo.find_commit(hash)?);
+        }
+
+        let new_head = *hashes.last().ok_or_else(|| anyhow!("Nothing to squash"))?;
+        let reflog_message = format!("ntangler: squash {} commits", plan.commit_count());
+        repo.reference_matching(&plan.branch_ref, new_head, true, plan.head, &reflog_message)
+            .map_err(|e| {
+                anyhow!(
+                    "{} moved while squashing, nothing was changed ({e})",
+                    plan.branch_ref
+                )
+            })?;
+        Ok(hashes)
+    }
+
+    /// Finds the oldest commit that any remote-tracking branch already contains.
+    pub(super) fn first_pushed<'c>(
+        repo: &Repository,
+        commits: &'c [Commit],
+    ) -> anyhow::Result<Option<&'c Commit<'c>>> {
+        let remote_tips = repo
+            .references_glob("refs/remotes/*")?
+            .filter_map(|reference| reference.ok()?.target())
+            .collect::<Vec<Oid>>();
+        for commit in commits {
+            for tip in &remote_tips {
+                if *tip == commit.id() || repo.graph_descendant_of(*tip, commit.id())? {
+                    return Ok(Some(commit));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The scope of a conventional commit summary, such as `api` in `feat(api): add route`.
+fn scope(summary: Option<&str>) -> Option<&str> {
+    let header = summary?.split_once(':')?.0;
+    let (_, scope) = header.split_once('(')?;
+    scope.trim_end_matches('!').strip_suffix(')')
+}
+
+fn short_id(commit: &Commit) -> String {
+    commit.id().to_string()[..7].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope() {
+        assert_eq!(scope(Some("feat(api): add route")), Some("api"));
+        assert_eq!(scope(Some("fix(api)!: drop route")), Some("api"));
+        assert_eq!(scope(Some("chore: tidy")), None);
+        assert_eq!(scope(None), None);
+    }
+}
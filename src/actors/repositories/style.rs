@@ -0,0 +1,46 @@
+use git2::Repository;
+
+use super::{notes, CommitLedger, GitRepository};
+
+/// How far back to look for commits worth imitating before giving up.
+const MAX_SCANNED: usize = 200;
+
+impl GitRepository {
+    /// The messages of the latest commits on the branch written by people rather than
+    /// ntangler, newest first, for the LLM to match in tone, scope names and body length.
+    pub(crate) fn style_examples(&self) -> anyhow::Result<Vec<String>> {
+        let count = self.repo_info.style_examples;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let repo = Repository::open(&self.repo_info.path)?;
+        let branch = self.branch(&repo)?;
+        let Ok(tip) = repo.find_reference(&format!("refs/heads/{branch}")) else {
+            return Ok(Vec::new());
+        };
+        let entries = CommitLedger::open(&repo).entries()?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(tip.peel_to_commit()?.id())?;
+        let mut examples = Vec::new();
+        for oid in revwalk.take(MAX_SCANNED) {
+            let commit = repo.find_commit(oid?)?;
+            if commit.parent_count() > 1 || CommitLedger::is_ntangler_commit(&entries, &commit) {
+                continue;
+            }
+            // The ledger is local, but notes travel with commits made on other clones.
+            if repo.find_note(Some(notes::NOTES_REF), commit.id()).is_ok() {
+                continue;
+            }
+            let message = commit.message().unwrap_or_default().trim();
+            if message.is_empty() {
+                continue;
+            }
+            examples.push(message.to_string());
+            if examples.len() == count {
+                break;
+            }
+        }
+        Ok(examples)
+    }
+}
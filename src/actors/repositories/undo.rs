@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use git2::{ObjectType, Oid, Repository};
+
+use crate::models::OperationInProgress;
+
+use super::shadow::shadow_ref_name;
+use super::{CommitLedger, GitRepository};
+
+/// Files whose commit was undone, kept in `.git/ntangler/held` along with the content they had
+/// at the time, so polling leaves them alone until they are edited again.
+pub(crate) struct HeldFiles {
+    path: PathBuf,
+    workdir: Option<PathBuf>,
+}
+
+impl HeldFiles {
+    pub(crate) fn open(repo: &Repository) -> HeldFiles {
+        HeldFiles {
+            path: repo.path().join("ntangler").join("held"),
+            workdir: repo.workdir().map(Path::to_path_buf),
+        }
+    }
+
+    /// Holds `files` at their current working tree content.
+    pub(crate) fn hold(&self, files: &[PathBuf]) -> anyhow::Result<()> {
+        let mut held = self.read()?;
+        for file in files {
+            let content = self.content_id(&file.to_string_lossy());
+            held.insert(file.to_string_lossy().to_string(), content);
+        }
+        self.write(&held)
+    }
+
+    /// Drops held files from `modified_files`, releasing any that have been edited or are no
+    /// longer modified at all.
+    pub(crate) fn filter(&self, modified_files: Vec<String>) -> anyhow::Result<Vec<String>> {
+        let held = self.read()?;
+        if held.is_empty() {
+            return Ok(modified_files);
+        }
+
+        let mut still_held = BTreeMap::new();
+        let mut files = Vec::new();
+        for file in modified_files {
+            match held.get(&file) {
+                Some(content) if *content == self.content_id(&file) => {
+                    still_held.insert(file, *content);
+                }
+                _ => files.push(file),
+            }
+        }
+        if still_held != held {
+            self.write(&still_held)?;
+        }
+        Ok(files)
+    }
+
+    fn content_id(&self, file: &str) -> Option<Oid> {
+        let workdir = self.workdir.as_ref()?;
+        Oid::hash_file(ObjectType::Blob, workdir.join(file)).ok()
+    }
+
+    fn read(&self) -> anyhow::Result<BTreeMap<String, Option<Oid>>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(contents
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(content, file)| (file.to_string(), Oid::from_str(content).ok()))
+            .collect())
+    }
+
+    fn write(&self, held: &BTreeMap<String, Option<Oid>>) -> anyhow::Result<()> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let contents: String = held
+            .iter()
+            .map(|(file, content)| {
+                let content = content.map(|oid| oid.to_string()).unwrap_or_default();
+                format!("{content}\t{file}\n")
+            })
+            .collect();
+        Ok(fs::write(&self.path, contents)?)
+    }
+}
+
+/// The commit `ntangler undo` took back.
+#[derive(Debug)]
+pub(crate) struct UndoneCommit {
+    pub(crate) hash: Oid,
+    pub(crate) target_file: PathBuf,
+    pub(crate) held_files: Vec<PathBuf>,
+    summary: String,
+}
+
+impl fmt::Display for UndoneCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let short_id = &self.hash.to_string()[..7];
+        write!(f, "Undid {short_id} {}", self.summary)?;
+        for file in &self.held_files {
+            write!(f, "\n  {} is held until it changes again", file.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl GitRepository {
+    /// Soft-resets ntangler's most recent commit, leaving its changes unstaged in the working
+    /// tree and holding its files back from the next poll.
+    ///
+    /// Nothing is touched unless the branch still points at that commit and it hasn't been
+    /// pushed. When `expected` is given, it must also be that commit.
+    pub(crate) fn undo_last_commit(&self, expected: Option<Oid>) -> anyhow::Result<UndoneCommit> {
+        let repo = Repository::open(&self.repo_info.path)?;
+        if let Some(operation) = OperationInProgress::from_state(repo.state()) {
+            bail!("Cannot undo while {operation}");
+        }
+
+        let Some((latest, target_file)) = CommitLedger::open(&repo).latest()? else {
+            bail!(
+                "ntangler has not committed anything in {}",
+                self.repo_info.nickname
+            );
+        };
+        let short_id = &latest.to_string()[..7];
+        if expected.is_some_and(|expected| expected != latest) {
+            bail!("{short_id} is ntangler's latest commit now; nothing was undone");
+        }
+
+        let branch = self.branch(&repo)?;
+        let reference = if self.repo_info.shadow {
+            shadow_ref_name(&branch)
+        } else {
+            format!("refs/heads/{branch}")
+        };
+        if repo.refname_to_id(&reference).ok() != Some(latest) {
+            bail!("{reference} has moved past ntangler's commit {short_id}; nothing was undone");
+        }
+
+        let commit = repo.find_commit(latest)?;
+        if Self::first_pushed(&repo, std::slice::from_ref(&commit))?.is_some() {
+            bail!("{short_id} has already been pushed; nothing was undone");
+        }
+        let Ok(parent) = commit.parent(0) else {
+            bail!("{short_id} is the root commit of {branch} and has no parent to undo it to; nothing was undone");
+        };
+        let diff = repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&commit.tree()?), None)?;
+        let held_files: Vec<PathBuf> = diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+            .map(Path::to_path_buf)
+            .collect();
+
+        let reflog_message = format!("ntangler: undo {short_id}");
+        repo.reference_matching(&reference, parent.id(), true, latest, &reflog_message)?;
+
+        // Shadow commits never touched the index, so only branch commits need unstaging.
+        let head_target = repo
+            .find_reference("HEAD")?
+            .symbolic_target()
+            .map(str::to_string);
+        if !held_files.is_empty() && head_target.as_deref() == Some(reference.as_str()) {
+            repo.reset_default(
+                Some(parent.as_object()),
+                held_files.iter().map(PathBuf::as_path),
+            )?;
+        }
+        HeldFiles::open(&repo).hold(&held_files)?;
+        CommitLedger::open(&repo).forget(latest)?;
+
+        Ok(UndoneCommit {
+            hash: latest,
+            target_file,
+            held_files,
+            summary: commit.summary().unwrap_or_default().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_held_files_released_once_edited() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+        let held = HeldFiles::open(&repo);
+        held.hold(&[PathBuf::from("a.txt")]).unwrap();
+
+        let modified = vec!["a.txt".to_string(), "b.txt".to_string()];
+        assert_eq!(held.filter(modified.clone()).unwrap(), vec!["b.txt"]);
+
+        fs::write(dir.path().join("a.txt"), "edited\n").unwrap();
+        assert_eq!(held.filter(modified.clone()).unwrap(), modified);
+        fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        assert_eq!(held.filter(modified.clone()).unwrap(), modified);
+    }
+}
@@ -1,13 +1,31 @@
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex, PoisonError};
+use std::time::Duration;
 
 use akton::prelude::*;
-use console::{Alignment, pad_str, Term};
+use console::{Alignment, Key, pad_str, Term};
 use owo_colors::OwoColorize;
 use tracing::*;
 
-use crate::messages::{DiffQueued, FinalizedCommit, GenerationStarted, SystemStarted};
+use crate::messages::{
+    CommitApproved, CommitDiscarded, CommitPending, CommitRejected, CommitUndone,
+    DescriptionEdited, DiffQueued, FinalizedCommit, GenerationFailed, GenerationStarted,
+    KeyPressed, PushCompleted, PushRejected, RegenerationRequested, RepositoryPaused,
+    RepositoryResumed, SecretDetected, ShadowHistoryArchived, SystemStarted, UndoRefused,
+    UndoRequested,
+};
 use crate::models::*;
 
+/// Held by whoever reads the terminal: the key listener while it waits for a key, or an editor.
+static TERMINAL: Mutex<()> = Mutex::new(());
+
+/// How many are waiting to borrow the terminal; the key listener keeps off it until none are.
+static TERMINAL_WANTED: AtomicUsize = AtomicUsize::new(0);
+
+/// How long the key listener waits for a key before giving others a turn at the terminal.
+const KEY_POLL: Duration = Duration::from_millis(100);
+
 #[akton_actor]
 #[derive(Clone)]
 pub(crate) struct Scribe {
@@ -18,6 +36,13 @@ pub(crate) struct Scribe {
     events: VecDeque<AppEvent>,
     session_count: usize,
     session_recommendation: SemVerImpact,
+    /// The most recent commit, which the undo key takes back.
+    last_commit: Option<FinalizedCommit>,
+    /// Generated messages waiting for approval, oldest first; the review keys act on the front.
+    review_queue: VecDeque<CommitPending>,
+    /// Hands the key listener the message whose description to edit, or nothing when there is
+    /// none.
+    edit_requests: Option<mpsc::Sender<Option<DescriptionEdited>>>,
 }
 
 impl Scribe {
@@ -38,6 +63,8 @@ impl Scribe {
         actor.state.stderr = Some(Term::stderr());
         actor.state.tab = tab;
         actor.state.half_tab = half_tab;
+        let (edit_requests, edits) = mpsc::channel();
+        actor.state.edit_requests = Some(edit_requests);
 
         actor
             .setup
@@ -51,6 +78,10 @@ impl Scribe {
                 let app_event: AppEvent = msg.into();
                 Scribe::handle_commit_event(&mut actor.state, &app_event);
             })
+            .act_on::<GenerationFailed>(|actor, event| {
+                let app_event: AppEvent = event.message.clone().into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
             .act_on::<FinalizedCommit>(|actor, event| {
                 let msg = event.message.clone();
                 let app_event: AppEvent = msg.clone().into();
@@ -59,9 +90,159 @@ impl Scribe {
                     actor.state.session_recommendation.clone(),
                 );
                 // Update session_count to reflect the true number of Posted events
-                actor.state.session_count += 1;
+                if !msg.amended {
+                    actor.state.session_count += 1;
+                }
+                actor.state.last_commit = Some(msg);
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<CommitUndone>(|actor, event| {
+                let msg = event.message.clone();
+                if actor.state.last_commit.as_ref().is_some_and(|commit| commit.hash == msg.hash) {
+                    actor.state.last_commit = None;
+                }
+                actor.state.session_count = actor.state.session_count.saturating_sub(1);
+                let refusal_id = AppEvent::repository_undo_id(&msg.repository_nickname);
+                Scribe::handle_remove_event(&mut actor.state, &refusal_id);
+                let app_event: AppEvent = msg.into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<UndoRefused>(|actor, event| {
+                let app_event: AppEvent = event.message.clone().into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<CommitPending>(|actor, event| {
+                let msg = event.message.clone();
+                let app_event: AppEvent = msg.clone().into();
+                actor.state.review_queue.push_back(msg);
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<CommitDiscarded>(|actor, event| {
+                let app_event: AppEvent = event.message.clone().into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<DescriptionEdited>(|actor, event| {
+                let edited = &event.message;
+                // The message may have been decided while its description was being typed.
+                let Some(pending) = actor.state.review_queue.iter_mut().find(|pending| {
+                    pending.target_file == edited.target_file
+                        && pending.repository_nickname == edited.repository_nickname
+                }) else {
+                    return;
+                };
+                pending.commit_message.description = event.message.description.as_str().into();
+                pending.metadata.edited = true;
+                let app_event: AppEvent = pending.clone().into();
                 Scribe::handle_commit_event(&mut actor.state, &app_event);
             })
+            .act_on_async::<KeyPressed>(|actor, event| {
+                let key = event.message.key;
+                if key == 'e' {
+                    actor.state.request_edit();
+                }
+                let broker = actor.akton.get_broker().clone();
+
+                // While messages wait for review, the review keys decide the oldest one; undo
+                // still works alongside them.
+                let pending = actor.state.review_queue.front().cloned();
+                if let Some(pending) = pending.filter(|_| matches!(key, 'a' | 'r' | 'g')) {
+                    let decided = match key {
+                        'a' => {
+                            let commit_approved = CommitApproved::new(
+                                pending.target_file,
+                                pending.repository_nickname,
+                                pending.commit_message,
+                                pending.metadata,
+                                pending.amending,
+                            );
+                            Context::wrap_future(async move {
+                                broker
+                                    .emit_async(BrokerRequest::new(commit_approved), None)
+                                    .await;
+                            })
+                        }
+                        'r' => {
+                            let commit_discarded = CommitDiscarded::new(
+                                pending.target_file,
+                                pending.repository_nickname,
+                            );
+                            Context::wrap_future(async move {
+                                broker
+                                    .emit_async(BrokerRequest::new(commit_discarded), None)
+                                    .await;
+                            })
+                        }
+                        'g' => {
+                            let regeneration_requested = RegenerationRequested::new(
+                                pending.target_file,
+                                pending.repository_nickname,
+                            );
+                            Context::wrap_future(async move {
+                                broker
+                                    .emit_async(BrokerRequest::new(regeneration_requested), None)
+                                    .await;
+                            })
+                        }
+                        _ => return Context::noop(),
+                    };
+                    actor.state.review_queue.pop_front();
+                    actor.state.print_menu();
+                    return decided;
+                }
+
+                let Some(commit) = &actor.state.last_commit else {
+                    return Context::noop();
+                };
+                match key {
+                    'u' | 'U' => {
+                        let undo_requested = UndoRequested::new(
+                            commit.repository_nickname.clone(),
+                            commit.hash.clone(),
+                        );
+                        let broker = actor.akton.get_broker().clone();
+                        Context::wrap_future(async move {
+                            broker
+                                .emit_async(BrokerRequest::new(undo_requested), None)
+                                .await;
+                        })
+                    }
+                    _ => Context::noop(),
+                }
+            })
+            .act_on::<CommitRejected>(|actor, event| {
+                let msg = event.message.clone();
+                let app_event: AppEvent = msg.into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<SecretDetected>(|actor, event| {
+                let msg = event.message.clone();
+                let app_event: AppEvent = msg.into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<PushCompleted>(|actor, event| {
+                let msg = event.message.clone();
+                let app_event: AppEvent = msg.into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<PushRejected>(|actor, event| {
+                let msg = event.message.clone();
+                let app_event: AppEvent = msg.into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<ShadowHistoryArchived>(|actor, event| {
+                let msg = event.message.clone();
+                let app_event: AppEvent = msg.into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<RepositoryPaused>(|actor, event| {
+                let msg = event.message.clone();
+                let app_event: AppEvent = msg.into();
+                Scribe::handle_commit_event(&mut actor.state, &app_event);
+            })
+            .act_on::<RepositoryResumed>(|actor, event| {
+                let event_id = AppEvent::repository_paused_id(&event.message.repository_nickname);
+                Scribe::handle_remove_event(&mut actor.state, &event_id);
+            })
             .act_on::<SystemStarted>(|actor, _event| {
                 Scribe::handle_system_started(&mut actor.state);
             })
@@ -78,9 +259,98 @@ impl Scribe {
         actor.context.subscribe::<SystemStarted>().await;
         actor.context.subscribe::<DiffQueued>().await;
         actor.context.subscribe::<GenerationStarted>().await;
+        actor.context.subscribe::<GenerationFailed>().await;
         actor.context.subscribe::<FinalizedCommit>().await;
+        actor.context.subscribe::<CommitRejected>().await;
+        actor.context.subscribe::<SecretDetected>().await;
+        actor.context.subscribe::<PushCompleted>().await;
+        actor.context.subscribe::<PushRejected>().await;
+        actor.context.subscribe::<ShadowHistoryArchived>().await;
+        actor.context.subscribe::<RepositoryPaused>().await;
+        actor.context.subscribe::<RepositoryResumed>().await;
+        actor.context.subscribe::<CommitUndone>().await;
+        actor.context.subscribe::<UndoRefused>().await;
+        actor.context.subscribe::<CommitPending>().await;
+        actor.context.subscribe::<CommitDiscarded>().await;
 
-        actor.activate(None).await
+        let context = actor.activate(None).await;
+        Scribe::listen_for_keys(context.clone(), edits);
+        context
+    }
+
+    /// Reads keystrokes on their own thread, since reading blocks, and hands them to the scribe.
+    fn listen_for_keys(scribe: Context, edits: mpsc::Receiver<Option<DescriptionEdited>>) {
+        let term = Term::stdout();
+        if !term.is_term() {
+            return;
+        }
+        let runtime = tokio::runtime::Handle::current();
+        std::thread::spawn(move || loop {
+            if TERMINAL_WANTED.load(Ordering::SeqCst) > 0 {
+                std::thread::sleep(KEY_POLL);
+                continue;
+            }
+            let _terminal = TERMINAL.lock().unwrap_or_else(PoisonError::into_inner);
+            if !key_waiting(KEY_POLL) {
+                continue;
+            }
+            match term.read_key() {
+                Ok(Key::Char(key)) => {
+                    runtime.block_on(scribe.emit_async(KeyPressed::new(key), None));
+                    // The scribe always answers an edit key, and the terminal is ours until it does.
+                    if key == 'e' {
+                        if let Ok(Some(mut edited)) = edits.recv() {
+                            if let Some(description) = Scribe::read_description(&term, &edited.description) {
+                                edited.description = description;
+                                runtime.block_on(scribe.emit_async(edited, None));
+                            }
+                        }
+                    }
+                }
+                Ok(_) => {}
+                // Ctrl-C interrupts the read and shuts everything down.
+                Err(_) => break,
+            }
+        });
+    }
+
+    /// Runs `f`, typically an editor, with the terminal to itself: the key listener stops reading
+    /// until it returns, so no keystroke meant for `f` is taken for a dashboard key.
+    pub(crate) fn lend_terminal<T>(f: impl FnOnce() -> T) -> T {
+        TERMINAL_WANTED.fetch_add(1, Ordering::SeqCst);
+        let terminal = TERMINAL.lock().unwrap_or_else(PoisonError::into_inner);
+        TERMINAL_WANTED.fetch_sub(1, Ordering::SeqCst);
+        let term = Term::stdout();
+        let _ = term.show_cursor();
+        let result = f();
+        let _ = term.hide_cursor();
+        drop(terminal);
+        result
+    }
+
+    /// Lets the user rewrite a description on the line below the footer.
+    fn read_description(term: &Term, description: &str) -> Option<String> {
+        let _ = term.move_cursor_to(0, DISPLAY_WINDOW + 4);
+        let _ = term.clear_line();
+        let _ = term.write_str(&format!("{}Description: ", *HALFTAB));
+        let _ = term.show_cursor();
+        let edited = term.read_line_initial_text(description);
+        let _ = term.hide_cursor();
+        let _ = term.clear_line();
+        edited.ok().filter(|edited| !edited.trim().is_empty())
+    }
+
+    fn request_edit(&self) {
+        let edit = self.review_queue.front().map(|pending| {
+            DescriptionEdited::new(
+                pending.target_file.clone(),
+                pending.repository_nickname.clone(),
+                pending.commit_message.description.to_string(),
+            )
+        });
+        if let Some(edit_requests) = &self.edit_requests {
+            let _ = edit_requests.send(edit);
+        }
     }
 
     fn handle_system_started(actor: &mut Scribe) {
@@ -108,6 +378,17 @@ impl Scribe {
         scribe.print_menu();
     }
 
+    fn handle_remove_event(scribe: &mut Scribe, event_id: &str) {
+        let previous_events = scribe.events.clone();
+        scribe.events.retain(|e| e.get_id() != event_id);
+        if scribe.events.len() == previous_events.len() {
+            return;
+        }
+
+        scribe.update_changed_events(&previous_events, &scribe.events);
+        scribe.print_menu();
+    }
+
     fn truncate_events(&mut self) {
         while self.events.len() > DISPLAY_WINDOW {
             self.events.pop_back();
@@ -120,11 +401,13 @@ impl Scribe {
         current_events: &VecDeque<AppEvent>,
     ) {
         if let Some(stderr) = &self.stderr {
-            for i in 0..current_events.len() {
+            for i in 0..current_events.len().max(previous_events.len()) {
                 if current_events.get(i) != previous_events.get(i) {
                     stderr.move_cursor_to(0, LIST_ROW + i).unwrap();
                     stderr.clear_line().unwrap();
-                    stderr.write_line(&current_events[i].to_string()).unwrap();
+                    if let Some(event) = current_events.get(i) {
+                        stderr.write_line(&event.to_string()).unwrap();
+                    }
                 }
             }
         }
@@ -176,7 +459,15 @@ impl Scribe {
     }
 
     fn format_footer(&self) -> String {
-        let instructions_text = "Ctrl-C (Stop)".style(*PALETTE_NEUTRAL_11).to_string();
+        let instructions_text = match self.review_queue.front() {
+            Some(pending) => format!(
+                "Review {} ({} waiting): a (Accept)  r (Reject)  g (Regenerate)  e (Edit)  u (Undo)",
+                pending.target_file.display(),
+                self.review_queue.len()
+            ),
+            None => "u (Undo)  Ctrl-C (Stop)".to_string(),
+        };
+        let instructions_text = instructions_text.style(*PALETTE_NEUTRAL_11).to_string();
         let (_, screen_width) = Term::stderr().size();
         let semver_recommendation_text_binding: SemVerImpactTerminal =
             (&self.session_recommendation).into();
@@ -263,3 +554,34 @@ impl Scribe {
         remaining_width
     }
 }
+
+/// Waits up to `timeout` for a key on stdin, without echoing it or waiting for a whole line.
+#[cfg(unix)]
+fn key_waiting(timeout: Duration) -> bool {
+    let fd = libc::STDIN_FILENO;
+    // SAFETY: `termios` is plain data that `tcgetattr` fills in, and `pollfd` outlives the call.
+    unsafe {
+        let mut original: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut original) != 0 {
+            // Not a terminal; let the read block as it always has.
+            return true;
+        }
+        let mut unbuffered = original;
+        unbuffered.c_lflag &= !(libc::ICANON | libc::ECHO);
+        libc::tcsetattr(fd, libc::TCSANOW, &unbuffered);
+        let mut stdin = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = libc::poll(&mut stdin, 1, timeout.as_millis() as libc::c_int);
+        libc::tcsetattr(fd, libc::TCSANOW, &original);
+        ready > 0
+    }
+}
+
+/// Elsewhere the read blocks, so an editor waits for the next key to get the terminal.
+#[cfg(not(unix))]
+fn key_waiting(_timeout: Duration) -> bool {
+    true
+}
@@ -0,0 +1,198 @@
+use anyhow::{anyhow, bail};
+
+use crate::models::config::NtanglerConfig;
+use crate::models::NtangledRepository;
+
+mod promote;
+mod squash;
+mod undo;
+
+const USAGE: &str = "\
+Usage:
+  ntangler                                  watch the configured repositories
+  ntangler promote [<nickname>] [--squash]  move shadow-ref commits onto the branch
+  ntangler squash [<nickname>] [--since <rev>] [--by-scope]
+                                            combine recent ntangler commits under a new message
+  ntangler undo [<nickname>]                take back ntangler's latest commit";
+
+/// One-shot commands that act on a configured repository and exit instead of starting the watcher.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Command {
+    Promote {
+        repository: Option<String>,
+        squash: bool,
+    },
+    Squash {
+        repository: Option<String>,
+        since: Option<String>,
+        by_scope: bool,
+    },
+    Undo {
+        repository: Option<String>,
+    },
+}
+
+impl Command {
+    /// Parses the arguments after the program name, returning `None` when no command was given.
+    pub(crate) fn parse(args: &[String]) -> anyhow::Result<Option<Command>> {
+        let Some((command, rest)) = args.split_first() else {
+            return Ok(None);
+        };
+
+        match command.as_str() {
+            "promote" => {
+                let mut repository = None;
+                let mut squash = false;
+                for arg in rest {
+                    match arg.as_str() {
+                        "--squash" => squash = true,
+                        flag if flag.starts_with('-') => bail!("Unknown option '{flag}'\n\n{USAGE}"),
+                        nickname if repository.is_none() => repository = Some(nickname.to_string()),
+                        extra => bail!("Unexpected argument '{extra}'\n\n{USAGE}"),
+                    }
+                }
+                Ok(Some(Command::Promote { repository, squash }))
+            }
+            "squash" => {
+                let mut repository = None;
+                let mut since = None;
+                let mut by_scope = false;
+                let mut rest = rest.iter();
+                while let Some(arg) = rest.next() {
+                    match arg.as_str() {
+                        "--by-scope" => by_scope = true,
+                        "--since" => match rest.next() {
+                            Some(rev) => since = Some(rev.to_string()),
+                            None => bail!("--since needs a revision\n\n{USAGE}"),
+                        },
+                        flag if flag.starts_with('-') => bail!("Unknown option '{flag}'\n\n{USAGE}"),
+                        nickname if repository.is_none() => repository = Some(nickname.to_string()),
+                        extra => bail!("Unexpected argument '{extra}'\n\n{USAGE}"),
+                    }
+                }
+                Ok(Some(Command::Squash {
+                    repository,
+                    since,
+                    by_scope,
+                }))
+            }
+            "undo" => match rest {
+                [] => Ok(Some(Command::Undo { repository: None })),
+                [nickname] if !nickname.starts_with('-') => Ok(Some(Command::Undo {
+                    repository: Some(nickname.to_string()),
+                })),
+                _ => bail!("Unexpected arguments to undo\n\n{USAGE}"),
+            },
+            "help" | "-h" | "--help" => bail!("{USAGE}"),
+            other => bail!("Unknown command '{other}'\n\n{USAGE}"),
+        }
+    }
+
+    /// Runs the command and returns what to tell the user.
+    pub(crate) async fn run(&self, config: &NtanglerConfig) -> anyhow::Result<String> {
+        match self {
+            Command::Promote { repository, squash } => {
+                promote::run(find_repository(config, repository.as_deref())?, *squash)
+            }
+            Command::Squash {
+                repository,
+                since,
+                by_scope,
+            } => {
+                let repository = find_repository(config, repository.as_deref())?;
+                squash::run(repository, since.as_deref(), *by_scope).await
+            }
+            Command::Undo { repository } => {
+                undo::run(find_repository(config, repository.as_deref())?)
+            }
+        }
+    }
+}
+
+/// Picks a repository by nickname, or the only one configured when no nickname is given.
+fn find_repository(
+    config: &NtanglerConfig,
+    nickname: Option<&str>,
+) -> anyhow::Result<NtangledRepository> {
+    let repository = match (nickname, config.repositories.as_slice()) {
+        (Some(nickname), repositories) => repositories
+            .iter()
+            .find(|repository| repository.nickname == nickname)
+            .ok_or_else(|| anyhow!("No repository is configured with the nickname '{nickname}'"))?,
+        (None, [only]) => only,
+        (None, _) => bail!("Several repositories are configured; name one by its nickname"),
+    };
+    Ok(repository.clone().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::config::RepositoryConfig;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_promote() {
+        assert_eq!(Command::parse(&[]).unwrap(), None);
+        assert_eq!(
+            Command::parse(&args(&["promote", "--squash", "my-project"])).unwrap(),
+            Some(Command::Promote {
+                repository: Some("my-project".to_string()),
+                squash: true,
+            })
+        );
+        assert!(Command::parse(&args(&["promote", "--force"])).is_err());
+        assert!(Command::parse(&args(&["frobnicate"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_squash() {
+        assert_eq!(
+            Command::parse(&args(&["squash", "--since", "HEAD~3", "--by-scope"])).unwrap(),
+            Some(Command::Squash {
+                repository: None,
+                since: Some("HEAD~3".to_string()),
+                by_scope: true,
+            })
+        );
+        assert!(Command::parse(&args(&["squash", "--since"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_undo() {
+        assert_eq!(
+            Command::parse(&args(&["undo"])).unwrap(),
+            Some(Command::Undo { repository: None })
+        );
+        assert_eq!(
+            Command::parse(&args(&["undo", "my-project"])).unwrap(),
+            Some(Command::Undo {
+                repository: Some("my-project".to_string()),
+            })
+        );
+        assert!(Command::parse(&args(&["undo", "--hard"])).is_err());
+    }
+
+    #[test]
+    fn test_find_repository() {
+        let repository = |nickname: &str| RepositoryConfig {
+            nickname: nickname.to_string(),
+            ..Default::default()
+        };
+        let config = NtanglerConfig {
+            repositories: vec![repository("one")],
+        };
+        assert_eq!(find_repository(&config, None).unwrap().nickname, "one");
+
+        let config = NtanglerConfig {
+            repositories: vec![repository("one"), repository("two")],
+        };
+        assert_eq!(find_repository(&config, Some("two")).unwrap().nickname, "two");
+        assert!(find_repository(&config, None).is_err());
+        assert!(find_repository(&config, Some("three")).is_err());
+    }
+}
@@ -0,0 +1,13 @@
+use anyhow::bail;
+
+use crate::actors::GitRepository;
+use crate::models::NtangledRepository;
+
+/// Moves a repository's shadow-ref history onto its branch.
+pub(super) fn run(repository: NtangledRepository, squash: bool) -> anyhow::Result<String> {
+    if !repository.shadow {
+        bail!("{} does not record commits on a shadow ref", repository.nickname);
+    }
+    let promotion = GitRepository::from(repository).promote(squash)?;
+    Ok(promotion.to_string())
+}
@@ -0,0 +1,59 @@
+use anyhow::bail;
+use tracing::warn;
+
+use crate::actors::{GitRepository, LlmClient};
+use crate::models::NtangledRepository;
+
+/// Replaces a run of ntangler commits with one commit per group, each under a freshly
+/// generated message.
+pub(super) async fn run(
+    repository: NtangledRepository,
+    since: Option<&str>,
+    by_scope: bool,
+) -> anyhow::Result<String> {
+    if repository.shadow {
+        bail!(
+            "{} records commits on a shadow ref; use promote --squash",
+            repository.nickname
+        );
+    }
+    let client = LlmClient::for_repositories(std::slice::from_ref(&repository))?;
+    let nickname = repository.nickname.clone();
+    let git_repository = GitRepository::from(repository);
+    let plan = git_repository.plan_squash(since, by_scope)?;
+    let examples = git_repository.style_examples()?;
+
+    let mut commit_messages = Vec::new();
+    let mut generations = Vec::new();
+    for group in &plan.groups {
+        let (commit_message, metadata) = client
+            .generate(
+                &nickname,
+                None,
+                group.diff.clone(),
+                group.messages.clone(),
+                examples.clone(),
+            )
+            .await?;
+        commit_messages.push(commit_message);
+        generations.push(metadata);
+    }
+
+    let hashes = git_repository.apply_squash(&plan, &commit_messages)?;
+    for (hash, metadata) in hashes.iter().zip(&generations) {
+        if let Err(e) = git_repository.attach_note(*hash, metadata) {
+            warn!("Failed to attach generation note to {hash}: {e}");
+        }
+    }
+    let mut output = format!(
+        "Squashed {} commits into {}:",
+        plan.commit_count(),
+        hashes.len()
+    );
+    for (hash, commit_message) in hashes.iter().zip(&commit_messages) {
+        let message = commit_message.to_string();
+        let summary = message.lines().next().unwrap_or_default();
+        output.push_str(&format!("\n  {} {summary}", &hash.to_string()[..7]));
+    }
+    Ok(output)
+}
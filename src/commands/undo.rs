@@ -0,0 +1,8 @@
+use crate::actors::GitRepository;
+use crate::models::NtangledRepository;
+
+/// Takes back ntangler's latest commit in a repository.
+pub(super) fn run(repository: NtangledRepository) -> anyhow::Result<String> {
+    let undone = GitRepository::from(repository).undo_last_commit(None)?;
+    Ok(undone.to_string())
+}
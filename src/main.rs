@@ -9,15 +9,17 @@ use anyhow::Result;
 use console::Term;
 use serde::Deserialize;
 use tokio::signal;
-use tracing::{error, info, Level};
+use tracing::{info, Level};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 use tracing_subscriber::fmt::format::FmtSpan;
 
 use crate::actors::Ntangler;
+use crate::commands::Command;
 use crate::models::config::NtanglerConfig;
 
 mod actors;
+mod commands;
 mod messages;
 mod models;
 
@@ -36,18 +38,17 @@ fn read_log_config(config_path: &PathBuf) -> LogConfig {
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     setup_tracing("ntangler", "config.toml");
 
-    if check_openai_api_key() {
-        info!("API Key Detected: The OPENAI_API_KEY environment variable is set.");
-    } else {
-        Term::stderr().write_line("Startup Error: The OPENAI_API_KEY environment variable is not set. Please set it to proceed. Consult the documentation to set the API key.")?;
-        error!("Startup Error: The OPENAI_API_KEY environment variable is not set.");
-        std::process::exit(1);
+    let args: Vec<String> = env::args().skip(1).collect();
+    match Command::parse(&args) {
+        Ok(Some(command)) => return run_command(command).await,
+        Ok(None) => {}
+        Err(e) => {
+            Term::stderr().write_line(&e.to_string())?;
+            std::process::exit(2);
+        }
     }
 
-    let config_path = find_config_path("ntangler", "config.toml")?;
-    let config_content = fs::read_to_string(&config_path)?;
-
-    let ntangler_config: NtanglerConfig = toml::from_str(&config_content)?;
+    let (ntangler_config, config_path) = load_config()?;
     info!(
         "Configuration Loaded: Config found at {}. Initializing...",
         config_path.display()
@@ -75,8 +76,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn check_openai_api_key() -> bool {
-    env::var("OPENAI_API_KEY").is_ok()
+async fn run_command(command: Command) -> Result<(), Box<dyn std::error::Error>> {
+    let (ntangler_config, _) = load_config()?;
+    match command.run(&ntangler_config).await {
+        Ok(output) => Term::stdout().write_line(&output)?,
+        Err(e) => {
+            Term::stderr().write_line(&format!("{e:#}"))?;
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+fn load_config() -> Result<(NtanglerConfig, PathBuf), Box<dyn std::error::Error>> {
+    let config_path = find_config_path("ntangler", "config.toml")?;
+    let config_content = fs::read_to_string(&config_path)?;
+    Ok((toml::from_str(&config_content)?, config_path))
 }
 
 fn find_config_path(
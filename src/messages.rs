@@ -1,19 +1,57 @@
+pub(crate) use commit_approved::CommitApproved;
+pub(crate) use commit_discarded::CommitDiscarded;
 pub(crate) use commit_message_generated::CommitMessageGenerated;
+pub(crate) use commit_pending::CommitPending;
+pub(crate) use commit_rejected::CommitRejected;
+pub(crate) use commit_undone::CommitUndone;
+pub(crate) use description_edited::DescriptionEdited;
 // pub(crate) use commit_authoring::CommitAuthoring;
 pub(crate) use diff_queued::DiffQueued;
 pub(crate) use file_change_detected::FileChangeDetected;
 pub(crate) use finalized_commit::FinalizedCommit;
+pub(crate) use generation_failed::GenerationFailed;
 pub(crate) use generation_started::GenerationStarted;
+pub(crate) use key_pressed::KeyPressed;
+pub(crate) use message_edited::MessageEdited;
 pub(crate) use poll_changes::RepositoryPollRequested;
+pub(crate) use push_completed::PushCompleted;
+pub(crate) use push_due::PushDue;
+pub(crate) use push_rejected::PushRejected;
+pub(crate) use regeneration_requested::RegenerationRequested;
+pub(crate) use repository_paused::RepositoryPaused;
+pub(crate) use repository_resumed::RepositoryResumed;
+pub(crate) use secret_detected::SecretDetected;
+pub(crate) use shadow_history_archived::ShadowHistoryArchived;
 pub(crate) use system_started::SystemStarted;
+pub(crate) use undo_refused::UndoRefused;
+pub(crate) use undo_requested::UndoRequested;
 
 mod poll_changes;
 mod system_started;
 
 mod commit_event;
 
+mod commit_approved;
+mod commit_discarded;
 mod commit_message_generated;
+mod commit_pending;
+mod commit_rejected;
+mod commit_undone;
+mod description_edited;
 mod diff_queued;
 mod file_change_detected;
 mod finalized_commit;
+mod generation_failed;
 mod generation_started;
+mod key_pressed;
+mod message_edited;
+mod push_completed;
+mod push_due;
+mod push_rejected;
+mod regeneration_requested;
+mod repository_paused;
+mod repository_resumed;
+mod secret_detected;
+mod shadow_history_archived;
+mod undo_refused;
+mod undo_requested;
@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+use crate::models::{CommitMessage, GenerationMetadata};
+
+/// Tells a repository to commit a reviewed message as it now stands.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct CommitApproved {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+    pub(crate) commit_message: CommitMessage,
+    pub(crate) metadata: GenerationMetadata,
+    /// The commit this one replaces, when the diff was taken to amend it.
+    pub(crate) amending: Option<String>,
+}
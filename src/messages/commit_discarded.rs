@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+/// Tells a repository that a reviewed message was rejected, so the file is held instead.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct CommitDiscarded {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+}
@@ -2,11 +2,14 @@ use std::path::PathBuf;
 
 use derive_new::new;
 
-use crate::models::CommitMessage;
+use crate::models::{CommitMessage, GenerationMetadata};
 
 /// Represents a successful commit message with its details.
 #[derive(new, Default, Debug, Clone)]
 pub(crate) struct CommitMessageGenerated {
     pub(crate) target_file: PathBuf,
     pub(crate) commit_message: CommitMessage,
+    pub(crate) metadata: GenerationMetadata,
+    /// The commit this one replaces, when the diff was taken to amend it.
+    pub(crate) amending: Option<String>,
 }
@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+use crate::models::{CommitMessage, GenerationMetadata};
+
+/// A generated message waiting in the review queue for someone to accept, reject, regenerate or
+/// edit it.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct CommitPending {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+    pub(crate) commit_message: CommitMessage,
+    pub(crate) metadata: GenerationMetadata,
+    /// The commit this one replaces, when the diff was taken to amend it.
+    pub(crate) amending: Option<String>,
+}
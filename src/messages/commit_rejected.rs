@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+/// Announces that a git hook refused one of ntangler's commits, with what the hook printed.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct CommitRejected {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+    pub(crate) hook: String,
+    pub(crate) output: String,
+}
@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+/// Announces that ntangler's latest commit was taken back and its file is held.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct CommitUndone {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+    pub(crate) hash: String,
+}
@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+/// The description the user typed for a message in the review queue.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct DescriptionEdited {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+    pub(crate) description: String,
+}
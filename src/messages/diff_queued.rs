@@ -9,5 +9,9 @@ pub(crate) struct DiffQueued {
     pub(crate) diff: String,
     pub(crate) target_file: PathBuf,
     pub(crate) repository_nickname: String,
+    /// Recent hand-written commit messages whose style the new one should match.
+    pub(crate) examples: Vec<String>,
+    /// The commit this one replaces, when the diff was taken to amend it.
+    pub(crate) amending: Option<String>,
     pub(crate) reply_address: Context,
 }
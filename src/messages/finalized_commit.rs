@@ -12,4 +12,11 @@ pub(crate) struct FinalizedCommit {
     pub(crate) repository_nickname: String,
     pub(crate) hash: String,
     pub(crate) commit_message: CommitMessage,
+    /// The commit replaced ntangler's previous commit for the same file.
+    pub(crate) amended: bool,
+    /// The message was changed in an editor before it was committed.
+    pub(crate) edited: bool,
+    /// The kind of provider that wrote the message, when one did.
+    #[new(default)]
+    pub(crate) provider: String,
 }
@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+use crate::models::FailureKind;
+
+/// Reports that no commit message could be generated for a file.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct GenerationFailed {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+    pub(crate) kind: FailureKind,
+    /// How many times the request was retried before giving up.
+    pub(crate) retries: u32,
+    pub(crate) error: String,
+}
@@ -0,0 +1,7 @@
+use derive_new::new;
+
+/// A key the user pressed in the terminal.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct KeyPressed {
+    pub(crate) key: char,
+}
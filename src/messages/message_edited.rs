@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+use crate::models::{CommitMessage, GenerationMetadata};
+
+/// Hands a repository back what the user saved in their editor for a generated message; nothing
+/// when they saved it empty.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct MessageEdited {
+    pub(crate) target_file: PathBuf,
+    pub(crate) commit_message: CommitMessage,
+    pub(crate) metadata: GenerationMetadata,
+    /// The commit this one replaces, when the diff was taken to amend it.
+    pub(crate) amending: Option<String>,
+    pub(crate) saved: Option<String>,
+}
@@ -0,0 +1,13 @@
+use derive_new::new;
+
+use crate::models::TimeStamp;
+
+/// Announces that a repository's commits reached its remote.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct PushCompleted {
+    pub(crate) when: TimeStamp,
+    pub(crate) repository_nickname: String,
+    pub(crate) remote: String,
+    pub(crate) branch: String,
+    pub(crate) commits: usize,
+}
@@ -0,0 +1,5 @@
+use akton::prelude::*;
+
+/// Tells a repository with an interval push policy that another interval has passed.
+#[akton_message]
+pub(crate) struct PushDue;
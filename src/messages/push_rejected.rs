@@ -0,0 +1,10 @@
+use derive_new::new;
+
+/// Announces that a push failed or the remote refused it, leaving the commits local.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct PushRejected {
+    pub(crate) repository_nickname: String,
+    pub(crate) remote: String,
+    pub(crate) branch: String,
+    pub(crate) reason: String,
+}
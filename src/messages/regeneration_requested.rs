@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+/// Asks a repository to send a file's diff for a fresh message.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct RegenerationRequested {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+}
@@ -0,0 +1,10 @@
+use derive_new::new;
+
+use crate::models::OperationInProgress;
+
+/// Announces that a repository stopped auto-committing because a git operation is underway.
+#[derive(new, Debug, Clone)]
+pub(crate) struct RepositoryPaused {
+    pub(crate) repository_nickname: String,
+    pub(crate) operation: OperationInProgress,
+}
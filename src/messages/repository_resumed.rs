@@ -0,0 +1,7 @@
+use derive_new::new;
+
+/// Announces that a paused repository is clean again and auto-commits continue.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct RepositoryResumed {
+    pub(crate) repository_nickname: String,
+}
@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+use derive_new::new;
+
+/// Reports a diff that looks like it contains a credential, which is neither sent to the LLM
+/// nor committed.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct SecretDetected {
+    pub(crate) target_file: PathBuf,
+    pub(crate) repository_nickname: String,
+    pub(crate) line: u32,
+    pub(crate) rule: String,
+}
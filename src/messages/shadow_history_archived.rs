@@ -0,0 +1,10 @@
+use derive_new::new;
+
+/// Announces that a branch moved past its shadow ref, so the shadow history recorded so far was
+/// kept under an archive ref and recording started over from the branch.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct ShadowHistoryArchived {
+    pub(crate) repository_nickname: String,
+    pub(crate) branch: String,
+    pub(crate) archive_ref: String,
+}
@@ -0,0 +1,8 @@
+use derive_new::new;
+
+/// Announces that an undo was asked for but refused, and why, leaving the repository as it was.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct UndoRefused {
+    pub(crate) repository_nickname: String,
+    pub(crate) reason: String,
+}
@@ -0,0 +1,8 @@
+use derive_new::new;
+
+/// Asks a repository to undo `hash`, as long as it is still ntangler's latest commit there.
+#[derive(new, Default, Debug, Clone)]
+pub(crate) struct UndoRequested {
+    pub(crate) repository_nickname: String,
+    pub(crate) hash: String,
+}
@@ -1,11 +1,15 @@
 pub(crate) use commit_message::CommitMessage;
 pub(crate) use commit_type::CommitType;
 pub(crate) use description::Description;
+pub(crate) use failure_kind::FailureKind;
 pub(crate) use file_name::Filename;
 pub(crate) use footer::Footer;
+pub(crate) use generation_metadata::GenerationMetadata;
 pub(crate) use oid::Oid;
+pub(crate) use operation_in_progress::OperationInProgress;
 pub(crate) use scope::Scope;
 pub(crate) use semver_impact::SemVerImpact;
+pub(crate) use signature::TangledSignature;
 pub(crate) use ntangled_repository::NtangledRepository;
 pub(crate) use time_stamp::TimeStamp;
 pub(crate) use ui::*;
@@ -13,9 +17,12 @@ pub(crate) use ui::*;
 mod commit_type;
 pub(crate) mod config;
 mod description;
+mod failure_kind;
 mod file_name;
 mod footer;
+mod generation_metadata;
 mod oid;
+mod operation_in_progress;
 mod scope;
 mod semver_impact;
 mod time_stamp;
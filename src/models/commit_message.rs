@@ -136,6 +136,26 @@ impl<'de> Deserialize<'de> for CommitMessage {
 }
 
 impl CommitMessage {
+    /// Builds the root commit message for a repository's initial file set.
+    pub(crate) fn initial_commit(files: &[String]) -> CommitMessage {
+        let mut files = files.to_vec();
+        files.sort();
+        let noun = if files.len() == 1 { "file" } else { "files" };
+        let listing = files
+            .iter()
+            .map(|file| format!("- {file}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        CommitMessage {
+            commit_type: "chore".into(),
+            scope: None,
+            description: "initial commit".into(),
+            body: format!("Add the initial {} {noun}:\n\n{listing}", files.len()),
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn calculate_footers(
         footers: &mut Vec<Footer>,
         commit_type: &CommitType,
@@ -398,6 +418,21 @@ mod tests {
         assert_eq!(format!("{}", commit_details), expected_output);
     }
 
+    #[test]
+    fn test_initial_commit_lists_sorted_files() {
+        let files = vec!["src/main.rs".to_string(), "Cargo.toml".to_string()];
+        let commit_message = CommitMessage::initial_commit(&files);
+
+        assert_eq!(commit_message.commit_type, "chore".into());
+        assert_eq!(commit_message.scope, None);
+        assert_eq!(commit_message.description, "initial commit".into());
+        assert_eq!(
+            commit_message.body,
+            "Add the initial 2 files:\n\n- Cargo.toml\n- src/main.rs"
+        );
+        assert_eq!(commit_message.semver_impact, SemVerImpact::NoImpact);
+    }
+
     #[test]
     #[traced_test]
     fn test_commit_details_partial() {
@@ -1,5 +1,17 @@
 pub(crate) use repository_config::RepositoryConfig;
 pub(crate) use ntangler_config::NtanglerConfig;
+pub(crate) use signing_config::SigningConfig;
+pub(crate) use identity_config::{AttributionTrailer, IdentityConfig, SignatureConfig};
+pub(crate) use provider_config::{ProviderConfig, ResponseFormat};
+pub(crate) use push_config::{PushConfig, PushPolicy};
+pub(crate) use redaction_config::{RedactionConfig, RedactionPattern};
+pub(crate) use retry_config::RetryConfig;
 
 mod repository_config;
 mod ntangler_config;
+mod signing_config;
+mod identity_config;
+mod provider_config;
+mod push_config;
+mod redaction_config;
+mod retry_config;
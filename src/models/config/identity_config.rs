@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Footer, TangledSignature};
+
+/// Who ntangler commits as in a repository, and how its generated messages are attributed.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct IdentityConfig {
+    /// Author of ntangler's commits. Defaults to git's `user.name` and `user.email`.
+    pub(crate) author: Option<SignatureConfig>,
+    /// Committer of ntangler's commits, e.g. a bot account. Defaults to the author.
+    pub(crate) committer: Option<SignatureConfig>,
+    /// Trailer appended to every generated commit message.
+    #[serde(default)]
+    pub(crate) trailer: AttributionTrailer,
+}
+
+/// A name and email pair for a git signature.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct SignatureConfig {
+    pub(crate) name: String,
+    pub(crate) email: String,
+}
+
+/// Marks a commit message as machine-written so audits can tell it apart from human ones.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum AttributionTrailer {
+    #[default]
+    None,
+    /// `Generated-by: ntangler <version>`
+    GeneratedBy,
+    /// `Co-authored-by: <committer>`, crediting the committer identity when it isn't also the
+    /// author.
+    CoAuthoredBy,
+}
+
+impl AttributionTrailer {
+    /// Builds the trailer footer for a commit by `author` made by `committer`.
+    pub(crate) fn footer(
+        &self,
+        author: &TangledSignature,
+        committer: &TangledSignature,
+    ) -> Option<Footer> {
+        match self {
+            AttributionTrailer::None => None,
+            AttributionTrailer::GeneratedBy => Some(Footer {
+                token: "Generated-by".to_string(),
+                value: format!("ntangler {}", env!("CARGO_PKG_VERSION")),
+            }),
+            // Nobody co-authors their own commit.
+            AttributionTrailer::CoAuthoredBy if committer.to_string() == author.to_string() => None,
+            AttributionTrailer::CoAuthoredBy => Some(Footer {
+                token: "Co-authored-by".to_string(),
+                value: committer.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_identity_config() {
+        let identity: IdentityConfig = toml::from_str(
+            r#"
+            trailer = "co-authored-by"
+            committer = { name = "ntangler bot", email = "bot@example.com" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(identity.author, None);
+        assert_eq!(identity.trailer, AttributionTrailer::CoAuthoredBy);
+        assert_eq!(identity.committer.unwrap().name, "ntangler bot");
+    }
+
+    #[test]
+    fn test_trailer_footers() {
+        let author = TangledSignature::new("Alice", "alice@example.com");
+        let committer = TangledSignature::new("ntangler bot", "bot@example.com");
+
+        assert_eq!(AttributionTrailer::None.footer(&author, &committer), None);
+
+        let co_authored_by = AttributionTrailer::CoAuthoredBy.footer(&author, &committer).unwrap();
+        assert_eq!(co_authored_by.to_string(), "Co-authored-by: ntangler bot <bot@example.com>");
+        assert_eq!(AttributionTrailer::CoAuthoredBy.footer(&author, &author), None);
+
+        let generated_by = AttributionTrailer::GeneratedBy.footer(&author, &committer).unwrap();
+        assert_eq!(generated_by.token, "Generated-by");
+        assert!(generated_by.value.starts_with("ntangler "));
+    }
+}
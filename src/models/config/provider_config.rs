@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+/// Which backend writes a repository's commit messages, chosen with `kind`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub(crate) enum ProviderConfig {
+    /// ntangler's own protocol: POST `{"diff": ...}` and read back a commit message. The token
+    /// comes from `NTANGLER_API_TOKEN`.
+    Ntangler {
+        /// Falls back to `NTANGLER_ENDPOINT`.
+        endpoint: Option<String>,
+    },
+    /// Any server speaking OpenAI's `/v1/chat/completions`: OpenAI itself, vLLM, LM Studio,
+    /// llama.cpp server and the like.
+    #[serde(rename = "openai")]
+    OpenAi {
+        /// The API's base URL, up to and including `/v1`.
+        #[serde(default = "default_openai_endpoint")]
+        endpoint: String,
+        model: String,
+        temperature: Option<f32>,
+        /// The environment variable holding the API key. Local servers usually need none.
+        #[serde(default = "default_openai_api_key_env")]
+        api_key_env: String,
+        #[serde(default)]
+        response_format: ResponseFormat,
+    },
+    /// A local Ollama server's `/api/chat`, so diffs never leave the machine.
+    Ollama {
+        #[serde(default = "default_ollama_endpoint")]
+        endpoint: String,
+        model: String,
+        temperature: Option<f32>,
+        /// How long Ollama keeps the model loaded after a request, as a duration like `"10m"`;
+        /// `"-1m"` keeps it loaded indefinitely. Ollama's own default applies when unset.
+        keep_alive: Option<String>,
+    },
+    /// Anthropic's Messages API, forced to answer through a tool whose input is the message.
+    Anthropic {
+        #[serde(default = "default_anthropic_endpoint")]
+        endpoint: String,
+        model: String,
+        #[serde(default = "default_max_tokens")]
+        max_tokens: u32,
+        temperature: Option<f32>,
+        /// The environment variable holding the API key.
+        #[serde(default = "default_anthropic_api_key_env")]
+        api_key_env: String,
+        /// Sent as the `anthropic-version` header.
+        #[serde(default = "default_anthropic_version")]
+        version: String,
+    },
+    /// Rules over the changed paths and lines, for when no model can be reached at all.
+    Heuristic,
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        ProviderConfig::Ntangler { endpoint: None }
+    }
+}
+
+/// How a chat completions request asks for JSON.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ResponseFormat {
+    /// Constrain the reply to the commit message schema.
+    #[default]
+    JsonSchema,
+    /// Ask only for some JSON object, for servers without schema support.
+    JsonObject,
+    /// Rely on the prompt alone.
+    None,
+}
+
+fn default_openai_endpoint() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_openai_api_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+
+fn default_ollama_endpoint() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_anthropic_endpoint() -> String {
+    "https://api.anthropic.com".to_string()
+}
+
+fn default_max_tokens() -> u32 {
+    1024
+}
+
+fn default_anthropic_api_key_env() -> String {
+    "ANTHROPIC_API_KEY".to_string()
+}
+
+fn default_anthropic_version() -> String {
+    "2023-06-01".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_provider() {
+        let provider: ProviderConfig = toml::from_str(
+            r#"
+            kind = "ntangler"
+            endpoint = "http://localhost:8080"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider,
+            ProviderConfig::Ntangler {
+                endpoint: Some("http://localhost:8080".to_string())
+            }
+        );
+        assert_eq!(
+            toml::from_str::<ProviderConfig>(r#"kind = "heuristic""#).unwrap(),
+            ProviderConfig::Heuristic
+        );
+        assert!(toml::from_str::<ProviderConfig>(r#"kind = "carrier-pigeon""#).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_openai_provider() {
+        let provider: ProviderConfig = toml::from_str(
+            r#"
+            kind = "openai"
+            endpoint = "http://localhost:1234/v1"
+            model = "qwen2.5-coder-7b-instruct"
+            temperature = 0.2
+            response_format = "json-object"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider,
+            ProviderConfig::OpenAi {
+                endpoint: "http://localhost:1234/v1".to_string(),
+                model: "qwen2.5-coder-7b-instruct".to_string(),
+                temperature: Some(0.2),
+                api_key_env: "OPENAI_API_KEY".to_string(),
+                response_format: ResponseFormat::JsonObject,
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_ollama_provider() {
+        let provider: ProviderConfig = toml::from_str(
+            r#"
+            kind = "ollama"
+            model = "llama3.1:8b"
+            keep_alive = "30m"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider,
+            ProviderConfig::Ollama {
+                endpoint: "http://localhost:11434".to_string(),
+                model: "llama3.1:8b".to_string(),
+                temperature: None,
+                keep_alive: Some("30m".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_anthropic_provider() {
+        let provider: ProviderConfig = toml::from_str(
+            r#"
+            kind = "anthropic"
+            model = "claude-3-5-haiku-latest"
+            max_tokens = 512
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider,
+            ProviderConfig::Anthropic {
+                endpoint: "https://api.anthropic.com".to_string(),
+                model: "claude-3-5-haiku-latest".to_string(),
+                max_tokens: 512,
+                temperature: None,
+                api_key_env: "ANTHROPIC_API_KEY".to_string(),
+                version: "2023-06-01".to_string(),
+            }
+        );
+    }
+}
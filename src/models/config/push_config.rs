@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Where and when a repository pushes the commits ntangler makes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PushConfig {
+    #[serde(default = "default_remote")]
+    pub(crate) remote: String,
+    #[serde(default)]
+    pub(crate) policy: PushPolicy,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        PushConfig {
+            remote: default_remote(),
+            policy: PushPolicy::default(),
+        }
+    }
+}
+
+fn default_remote() -> String {
+    "origin".to_string()
+}
+
+/// How often ntangler pushes.
+///
+/// Written as `"every-commit"`, `"on-shutdown"`, `{ after-commits = 5 }` or `{ interval = 300 }`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum PushPolicy {
+    #[default]
+    EveryCommit,
+    /// Push once this many commits are waiting.
+    AfterCommits(usize),
+    /// Push waiting commits every this many seconds.
+    Interval(u64),
+    OnShutdown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_push_policies() {
+        let push: PushConfig = toml::from_str(r#"policy = { after-commits = 5 }"#).unwrap();
+        assert_eq!(push.remote, "origin");
+        assert_eq!(push.policy, PushPolicy::AfterCommits(5));
+
+        let push: PushConfig = toml::from_str(
+            r#"
+            remote = "backup"
+            policy = "on-shutdown"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(push.remote, "backup");
+        assert_eq!(push.policy, PushPolicy::OnShutdown);
+
+        let push: PushConfig = toml::from_str(r#"policy = { interval = 300 }"#).unwrap();
+        assert_eq!(push.policy, PushPolicy::Interval(300));
+
+        let push: PushConfig = toml::from_str("").unwrap();
+        assert_eq!(push.policy, PushPolicy::EveryCommit);
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// What to strip from a repository's diffs before they are sent to the LLM. Commits still
+/// record the real content.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct RedactionConfig {
+    /// Globs, relative to the repository, of files whose changes are withheld entirely.
+    #[serde(default)]
+    pub(crate) paths: Vec<String>,
+    #[serde(default)]
+    pub(crate) patterns: Vec<RedactionPattern>,
+}
+
+impl RedactionConfig {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.patterns.is_empty()
+    }
+}
+
+/// A regex whose matches are swapped for `replacement`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RedactionPattern {
+    pub(crate) pattern: String,
+    #[serde(default = "default_replacement")]
+    pub(crate) replacement: String,
+}
+
+fn default_replacement() -> String {
+    "[REDACTED]".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_redaction() {
+        let redaction: RedactionConfig = toml::from_str(
+            r#"
+            paths = ["customers/**"]
+            patterns = [
+                { pattern = "CUST-[0-9]{6}", replacement = "<customer-id>" },
+                { pattern = "[a-z.]+@example\\.com" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(redaction.paths, vec!["customers/**"]);
+        assert_eq!(redaction.patterns[0].replacement, "<customer-id>");
+        assert_eq!(redaction.patterns[1].replacement, "[REDACTED]");
+        assert!(toml::from_str::<RedactionConfig>("").unwrap().is_empty());
+    }
+}
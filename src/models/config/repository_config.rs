@@ -2,10 +2,51 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use crate::models::config::{
+    IdentityConfig, ProviderConfig, PushConfig, RedactionConfig, RetryConfig, SigningConfig,
+};
+
 /// Represents a repository configuration.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub(crate) struct RepositoryConfig {
     pub(crate) nickname: String,
     pub(crate) path: PathBuf,
     pub(crate) branch_name: String,
+    #[serde(default)]
+    pub(crate) signing: SigningConfig,
+    #[serde(default)]
+    pub(crate) identity: IdentityConfig,
+    #[serde(default)]
+    pub(crate) run_hooks: bool,
+    #[serde(default)]
+    pub(crate) shadow: bool,
+    /// Seconds within which another save of the same file amends ntangler's previous commit
+    /// instead of adding one; 0 turns amending off.
+    #[serde(default)]
+    pub(crate) amend_window: u64,
+    /// Queue generated messages for approval in the dashboard instead of committing them.
+    #[serde(default)]
+    pub(crate) review: bool,
+    /// Open each message in `$GIT_EDITOR` or `$EDITOR` and commit whatever is saved.
+    #[serde(default)]
+    pub(crate) editor: bool,
+    /// The editor to open messages in, in place of the one git would pick.
+    pub(crate) editor_command: Option<String>,
+    #[serde(default)]
+    pub(crate) provider: ProviderConfig,
+    /// Providers to try in order when `provider` times out, errors or answers with something
+    /// that isn't a commit message.
+    #[serde(default)]
+    pub(crate) fallbacks: Vec<ProviderConfig>,
+    #[serde(default)]
+    pub(crate) retry: RetryConfig,
+    /// A file, relative to the repository, whose contents replace the prompt sent with each diff.
+    pub(crate) prompt_template: Option<PathBuf>,
+    /// How many of the branch's latest hand-written commit messages to send along as examples of
+    /// the project's style; 0 sends none.
+    #[serde(default)]
+    pub(crate) style_examples: usize,
+    #[serde(default)]
+    pub(crate) redaction: RedactionConfig,
+    pub(crate) push: Option<PushConfig>,
 }
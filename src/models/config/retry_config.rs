@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How patiently a repository's provider is called.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub(crate) struct RetryConfig {
+    /// Seconds to wait for one response.
+    pub(crate) timeout: u64,
+    /// Further attempts after a timeout, a connection failure, a 429 or a 5xx response.
+    pub(crate) max_retries: u32,
+    /// Milliseconds before the first retry, doubling for each one after, unless the response
+    /// says how long to wait with `Retry-After`.
+    pub(crate) backoff: u64,
+    /// Seconds any one wait is capped at.
+    pub(crate) max_backoff: u64,
+    /// Consecutive failed attempts after which the provider is left alone for a while.
+    pub(crate) failure_threshold: u32,
+    /// Seconds the provider is first left alone for, doubling each time it fails again.
+    pub(crate) open_for: u64,
+}
+
+impl RetryConfig {
+    pub(crate) fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+
+    /// The wait before retry number `retry`, counting from 0.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let backoff =
+            Duration::from_millis(self.backoff).saturating_mul(2u32.saturating_pow(retry));
+        backoff.min(Duration::from_secs(self.max_backoff))
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            timeout: 30,
+            max_retries: 3,
+            backoff: 500,
+            max_backoff: 30,
+            failure_threshold: 5,
+            open_for: 30,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let retry: RetryConfig = toml::from_str(
+            r#"
+            backoff = 1000
+            max_backoff = 5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(retry.timeout, 30);
+        assert_eq!(retry.backoff(0), Duration::from_secs(1));
+        assert_eq!(retry.backoff(2), Duration::from_secs(4));
+        assert_eq!(retry.backoff(3), Duration::from_secs(5));
+        assert_eq!(retry.backoff(40), Duration::from_secs(5));
+    }
+}
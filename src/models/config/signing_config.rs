@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Per-repository overrides for git's commit signing settings.
+///
+/// Any field left unset falls back to the matching git config value.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct SigningConfig {
+    /// Overrides `commit.gpgsign`.
+    pub(crate) enabled: Option<bool>,
+    /// Overrides `gpg.format`: `openpgp`, `x509` or `ssh`.
+    pub(crate) format: Option<String>,
+    /// Overrides `user.signingkey`.
+    pub(crate) key: Option<String>,
+}
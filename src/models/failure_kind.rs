@@ -0,0 +1,44 @@
+use std::fmt;
+
+/// Why a provider could not write a commit message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum FailureKind {
+    /// No answer within the configured timeout.
+    Timeout,
+    /// The connection could not be made or broke off.
+    Unreachable,
+    /// Answered 429.
+    RateLimited,
+    /// Answered with a 5xx status.
+    ServerError,
+    /// Skipped because the provider has been failing.
+    CircuitOpen,
+    /// Answered with another error status, such as a bad key or an unknown model.
+    Rejected,
+    /// Answered with something that isn't a commit message.
+    #[default]
+    InvalidResponse,
+}
+
+impl FailureKind {
+    /// Whether the same request may well succeed later. Files that failed otherwise are held
+    /// until they are edited again rather than sent over and over.
+    pub(crate) fn is_transient(self) -> bool {
+        !matches!(self, FailureKind::Rejected | FailureKind::InvalidResponse)
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            FailureKind::Timeout => "timed out",
+            FailureKind::Unreachable => "unreachable",
+            FailureKind::RateLimited => "rate limited",
+            FailureKind::ServerError => "server error",
+            FailureKind::CircuitOpen => "backing off",
+            FailureKind::Rejected => "rejected",
+            FailureKind::InvalidResponse => "invalid response",
+        };
+        write!(f, "{kind}")
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+use std::time::Duration;
+
+use git2::{ObjectType, Oid};
+
+/// How a commit message came about, recorded as a git note on the commit for auditing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct GenerationMetadata {
+    /// The kind of provider that wrote the message.
+    pub(crate) provider: String,
+    /// Whether the repository's first provider failed and a fallback wrote the message.
+    pub(crate) fallback: bool,
+    pub(crate) endpoint: String,
+    pub(crate) model: Option<String>,
+    pub(crate) latency: Duration,
+    pub(crate) diff_bytes: usize,
+    /// The git blob hash of the request body sent to the endpoint.
+    pub(crate) prompt_hash: String,
+    /// How many pieces of the diff redaction rules kept from the endpoint.
+    pub(crate) redactions: usize,
+    pub(crate) edited: bool,
+    pub(crate) heuristic: bool,
+}
+
+impl GenerationMetadata {
+    /// Metadata for a message ntangler wrote itself instead of asking the endpoint.
+    pub(crate) fn heuristic(diff_bytes: usize) -> GenerationMetadata {
+        GenerationMetadata {
+            diff_bytes,
+            heuristic: true,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn hash_prompt(prompt: &[u8]) -> String {
+        Oid::hash_object(ObjectType::Blob, prompt)
+            .map(|oid| oid.to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for GenerationMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.provider.is_empty() {
+            writeln!(f, "provider: {}", self.provider)?;
+        }
+        if self.fallback {
+            writeln!(f, "fallback: true")?;
+        }
+        if !self.endpoint.is_empty() {
+            writeln!(f, "endpoint: {}", self.endpoint)?;
+        }
+        if let Some(model) = &self.model {
+            writeln!(f, "model: {model}")?;
+        }
+        if !self.heuristic {
+            writeln!(f, "latency-ms: {}", self.latency.as_millis())?;
+        }
+        writeln!(f, "diff-bytes: {}", self.diff_bytes)?;
+        if !self.prompt_hash.is_empty() {
+            writeln!(f, "prompt-hash: {}", self.prompt_hash)?;
+        }
+        if self.redactions > 0 {
+            writeln!(f, "redactions: {}", self.redactions)?;
+        }
+        writeln!(f, "edited: {}", self.edited)?;
+        write!(f, "heuristic: {}", self.heuristic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_text() {
+        let metadata = GenerationMetadata {
+            endpoint: "http://localhost:8080".to_string(),
+            model: Some("gpt-4o".to_string()),
+            latency: Duration::from_millis(1250),
+            diff_bytes: 42,
+            prompt_hash: GenerationMetadata::hash_prompt(b"{}"),
+            ..Default::default()
+        };
+        assert_eq!(
+            metadata.to_string(),
+            format!(
+                "endpoint: http://localhost:8080\nmodel: gpt-4o\nlatency-ms: 1250\ndiff-bytes: 42\nprompt-hash: {}\nedited: false\nheuristic: false",
+                metadata.prompt_hash
+            )
+        );
+        assert_eq!(
+            GenerationMetadata::heuristic(7).to_string(),
+            "diff-bytes: 7\nedited: false\nheuristic: true"
+        );
+    }
+}
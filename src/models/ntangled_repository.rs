@@ -2,14 +2,32 @@ use std::path::PathBuf;
 
 use akton::prelude::Arn;
 
-use crate::models::config::RepositoryConfig;
+use crate::models::config::{
+    IdentityConfig, ProviderConfig, PushConfig, RedactionConfig, RepositoryConfig, RetryConfig,
+    SigningConfig,
+};
 
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub(crate) struct NtangledRepository {
     pub(crate) akton_arn: Arn<'static>,
     pub(crate) nickname: String,
     pub(crate) path: PathBuf,
     pub(crate) branch_name: String,
+    pub(crate) signing: SigningConfig,
+    pub(crate) identity: IdentityConfig,
+    pub(crate) run_hooks: bool,
+    pub(crate) shadow: bool,
+    pub(crate) amend_window: u64,
+    pub(crate) review: bool,
+    pub(crate) editor: bool,
+    pub(crate) editor_command: Option<String>,
+    pub(crate) provider: ProviderConfig,
+    pub(crate) fallbacks: Vec<ProviderConfig>,
+    pub(crate) retry: RetryConfig,
+    pub(crate) prompt_template: Option<PathBuf>,
+    pub(crate) style_examples: usize,
+    pub(crate) redaction: RedactionConfig,
+    pub(crate) push: Option<PushConfig>,
 }
 
 impl From<RepositoryConfig> for NtangledRepository {
@@ -19,6 +37,21 @@ impl From<RepositoryConfig> for NtangledRepository {
             nickname: value.nickname,
             path: value.path,
             branch_name: value.branch_name,
+            signing: value.signing,
+            identity: value.identity,
+            run_hooks: value.run_hooks,
+            shadow: value.shadow,
+            amend_window: value.amend_window,
+            review: value.review,
+            editor: value.editor,
+            editor_command: value.editor_command,
+            provider: value.provider,
+            fallbacks: value.fallbacks,
+            retry: value.retry,
+            prompt_template: value.prompt_template,
+            style_examples: value.style_examples,
+            redaction: value.redaction,
+            push: value.push,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use std::fmt;
+
+use git2::RepositoryState;
+
+/// A git operation (merge, rebase, ...) underway in a repository, during which automatic commits are unsafe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct OperationInProgress(RepositoryState);
+
+impl OperationInProgress {
+    /// Returns `None` when the repository is clean and can be committed to.
+    pub(crate) fn from_state(state: RepositoryState) -> Option<OperationInProgress> {
+        match state {
+            RepositoryState::Clean => None,
+            state => Some(OperationInProgress(state)),
+        }
+    }
+}
+
+impl fmt::Display for OperationInProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operation = match self.0 {
+            RepositoryState::Clean => "no operation",
+            RepositoryState::Merge => "merge",
+            RepositoryState::Revert | RepositoryState::RevertSequence => "revert",
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => "cherry-pick",
+            RepositoryState::Bisect => "bisect",
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => "rebase",
+            RepositoryState::ApplyMailbox => "am",
+            RepositoryState::ApplyMailboxOrRebase => "am/rebase",
+        };
+        write!(f, "{operation} in progress")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_repository_has_no_operation() {
+        assert_eq!(OperationInProgress::from_state(RepositoryState::Clean), None);
+    }
+
+    #[test]
+    fn test_display_operation_in_progress() {
+        let rebase = OperationInProgress::from_state(RepositoryState::RebaseInteractive).unwrap();
+        assert_eq!(rebase.to_string(), "rebase in progress");
+
+        let cherry_pick = OperationInProgress::from_state(RepositoryState::CherryPickSequence).unwrap();
+        assert_eq!(cherry_pick.to_string(), "cherry-pick in progress");
+    }
+}
@@ -1,6 +1,10 @@
+use std::fmt;
+
 use chrono::{DateTime, TimeZone, Utc};
 use git2::{Signature, Time};
 
+use crate::models::config::SignatureConfig;
+
 #[derive(Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) struct TangledSignature {
     name: String,
@@ -8,6 +12,33 @@ pub(crate) struct TangledSignature {
     time: DateTime<Utc>,
 }
 
+impl TangledSignature {
+    pub(crate) fn new(name: &str, email: &str) -> TangledSignature {
+        TangledSignature {
+            name: name.to_string(),
+            email: email.to_string(),
+            time: Utc::now(),
+        }
+    }
+
+    /// Converts to a git signature stamped with the current time.
+    pub(crate) fn to_signature(&self) -> Result<Signature<'static>, git2::Error> {
+        Signature::now(&self.name, &self.email)
+    }
+}
+
+impl fmt::Display for TangledSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+impl From<&SignatureConfig> for TangledSignature {
+    fn from(value: &SignatureConfig) -> Self {
+        TangledSignature::new(&value.name, &value.email)
+    }
+}
+
 impl From<Signature<'_>> for TangledSignature {
     fn from(value: Signature<'_>) -> Self {
         TangledSignature {
@@ -32,3 +63,25 @@ fn convert_to_datetime(time: &Time) -> DateTime<Utc> {
         .expect("Invalid timestamp")
         + chrono::Duration::minutes(offset_minutes.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_signature() {
+        let signature = TangledSignature::new("Jane Doe", "jane@example.com");
+        assert_eq!(signature.to_string(), "Jane Doe <jane@example.com>");
+    }
+
+    #[test]
+    fn test_round_trip_git_signature() {
+        let signature: TangledSignature = Signature::now("Jane Doe", "jane@example.com")
+            .unwrap()
+            .into();
+        let git_signature = signature.to_signature().unwrap();
+
+        assert_eq!(git_signature.name(), Some("Jane Doe"));
+        assert_eq!(git_signature.email(), Some("jane@example.com"));
+    }
+}
@@ -33,6 +33,7 @@ pub static ref PALETTE_TERTIARY_9: Style  = *AMBER_9;
 pub static ref PALETTE_TERTIARY_8: Style  = *AMBER_8;
 
 pub static ref ALERT_COLOR: Style = *ORANGE_11;
+pub static ref DANGER_COLOR: Style = *RED_11;
 
 pub static ref PALETTE_NEUTRAL_13: Style = Style::new().white();
 pub static ref PALETTE_NEUTRAL_12: Style = *MAUVE_12;
@@ -6,7 +6,11 @@ use derive_new::new;
 use owo_colors::OwoColorize;
 use uuid::Uuid;
 
-use crate::messages::{DiffQueued, FinalizedCommit, GenerationStarted};
+use crate::messages::{
+    CommitDiscarded, CommitPending, CommitRejected, CommitUndone, DiffQueued, FinalizedCommit,
+    GenerationFailed, GenerationStarted, PushCompleted, PushRejected, RepositoryPaused, SecretDetected,
+    ShadowHistoryArchived, UndoRefused,
+};
 use crate::models::*;
 
 /// Represents a successful commit message with its details.
@@ -20,6 +24,30 @@ impl AppEvent {
     pub(crate) fn get_id(&self) -> &String {
         &self.event_id
     }
+
+    /// The id of the row that shows a repository's paused state.
+    pub(crate) fn repository_paused_id(repository_nickname: &str) -> String {
+        let simple_urn = format!("{}://paused", repository_nickname);
+        Uuid::new_v3(&Uuid::NAMESPACE_OID, simple_urn.as_ref()).to_string()
+    }
+
+    /// The id of the row that shows where a repository's shadow history was last archived.
+    pub(crate) fn repository_shadow_id(repository_nickname: &str) -> String {
+        let simple_urn = format!("{}://shadow", repository_nickname);
+        Uuid::new_v3(&Uuid::NAMESPACE_OID, simple_urn.as_ref()).to_string()
+    }
+
+    /// The id of the row that shows a repository's latest push.
+    pub(crate) fn repository_push_id(repository_nickname: &str) -> String {
+        let simple_urn = format!("{}://push", repository_nickname);
+        Uuid::new_v3(&Uuid::NAMESPACE_OID, simple_urn.as_ref()).to_string()
+    }
+
+    /// The id of the row that shows why a repository's latest undo was refused.
+    pub(crate) fn repository_undo_id(repository_nickname: &str) -> String {
+        let simple_urn = format!("{}://undo", repository_nickname);
+        Uuid::new_v3(&Uuid::NAMESPACE_OID, simple_urn.as_ref()).to_string()
+    }
 }
 
 impl Display for AppEvent {
@@ -56,6 +84,7 @@ impl From<FinalizedCommit> for AppEvent {
             .into();
         let repository = &value.repository_nickname.style(*REPO_COLOR);
         let filename = &value.target_file.display();
+        let via = via(&value.provider);
 
         let halftab = &HALFTAB.clone();
         let display_string = format!(
@@ -66,12 +95,20 @@ impl From<FinalizedCommit> for AppEvent {
                             {semver_impact} \
                             {filename:<COLUMN_HEADING_FIVE_LENGTH$} \
                             {commit_heading:<COLUMN_HEADING_SIX_LENGTH$} \
-                            {description:<COLUMN_HEADING_SEVEN_LENGTH$}"
+                            {description:<COLUMN_HEADING_SEVEN_LENGTH$}{via}"
         );
         AppEvent::new(event_id, display_string)
     }
 }
 
+/// Names the provider that wrote a message, for rows of repositories that have one.
+fn via(provider: &str) -> String {
+    if provider.is_empty() {
+        return String::new();
+    }
+    format!(" via {provider}").style(*STATUS_PENDING).to_string()
+}
+
 impl From<GenerationStarted> for AppEvent {
     fn from(value: GenerationStarted) -> Self {
         let namespace = Uuid::NAMESPACE_OID;
@@ -123,3 +160,316 @@ impl From<DiffQueued> for AppEvent {
         AppEvent::new(event_id, display_string)
     }
 }
+
+impl From<CommitRejected> for AppEvent {
+    fn from(value: CommitRejected) -> Self {
+        let namespace = Uuid::NAMESPACE_OID;
+
+        let simple_urn = format!("{}://{:?}", &value.repository_nickname, value.target_file);
+        let event_id = Uuid::new_v3(&namespace, simple_urn.as_ref()).to_string();
+        let time_stamp = "\u{2014}\u{2014}".style(*ALERT_COLOR);
+        let binding = &value.target_file.display();
+        let filename = &binding.style(*ALERT_COLOR);
+        let repository = &value.repository_nickname.style(*ALERT_COLOR);
+        let status = "REJECTED".style(*ALERT_COLOR).to_string();
+        let emdash = EMDASH.style(*ALERT_COLOR);
+        let first_line = value.output.lines().next().unwrap_or_default();
+        let reason = format!("{}: {}, held until edited", value.hook, first_line).style(*ALERT_COLOR).to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {filename:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {reason}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<CommitUndone> for AppEvent {
+    fn from(value: CommitUndone) -> Self {
+        let namespace = Uuid::NAMESPACE_OID;
+
+        let simple_urn = format!("{}://{:?}", &value.repository_nickname, value.target_file);
+        let event_id = Uuid::new_v3(&namespace, simple_urn.as_ref()).to_string();
+        let time_stamp = "\u{2014}\u{2014}".style(*STATUS_PENDING);
+        let binding = &value.target_file.display();
+        let filename = &binding.style(*FILENAME_PENDING);
+        let repository = &value.repository_nickname.style(*REPO_PENDING_COLOR);
+        let status = "UNDONE".style(*STATUS_PENDING).to_string();
+        let emdash = EMDASH.style(*STATUS_PENDING);
+        let oid = Oid::new(&value.hash);
+        let note = format!("held until edited, was {oid}").style(*STATUS_PENDING).to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {filename:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {note}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<CommitPending> for AppEvent {
+    fn from(value: CommitPending) -> Self {
+        let namespace = Uuid::NAMESPACE_OID;
+
+        let simple_urn = format!("{}://{:?}", &value.repository_nickname, value.target_file);
+        let event_id = Uuid::new_v3(&namespace, simple_urn.as_ref()).to_string();
+        let time_stamp = "\u{2014}\u{2014}".style(*ALERT_COLOR);
+        let binding = &value.target_file.display();
+        let filename = &binding.style(*ALERT_COLOR);
+        let repository = &value.repository_nickname.style(*ALERT_COLOR);
+        let status = "REVIEW".style(*ALERT_COLOR).to_string();
+        let emdash = EMDASH.style(*ALERT_COLOR);
+        let commit_heading: CommitHeadingTerminal = (
+            (&value.commit_message.commit_type).into(),
+            (&value.commit_message.scope).into(),
+            (&value.commit_message.is_breaking).into(),
+        )
+            .into();
+        let description: DescriptionTerminal = (&value.commit_message.description).into();
+        let via = via(&value.metadata.provider);
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {filename:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {commit_heading:<COLUMN_HEADING_SIX_LENGTH$} \
+                            {description:<COLUMN_HEADING_SEVEN_LENGTH$}{via}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<CommitDiscarded> for AppEvent {
+    fn from(value: CommitDiscarded) -> Self {
+        let namespace = Uuid::NAMESPACE_OID;
+
+        let simple_urn = format!("{}://{:?}", &value.repository_nickname, value.target_file);
+        let event_id = Uuid::new_v3(&namespace, simple_urn.as_ref()).to_string();
+        let time_stamp = "\u{2014}\u{2014}".style(*STATUS_PENDING);
+        let binding = &value.target_file.display();
+        let filename = &binding.style(*FILENAME_PENDING);
+        let repository = &value.repository_nickname.style(*REPO_PENDING_COLOR);
+        let status = "DISCARDED".style(*STATUS_PENDING).to_string();
+        let emdash = EMDASH.style(*STATUS_PENDING);
+        let note = "held until edited".style(*STATUS_PENDING).to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {filename:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {note}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<SecretDetected> for AppEvent {
+    fn from(value: SecretDetected) -> Self {
+        let namespace = Uuid::NAMESPACE_OID;
+
+        let simple_urn = format!("{}://{:?}", &value.repository_nickname, value.target_file);
+        let event_id = Uuid::new_v3(&namespace, simple_urn.as_ref()).to_string();
+        let time_stamp = "\u{2014}\u{2014}".style(*DANGER_COLOR);
+        let binding = &value.target_file.display();
+        let filename = &binding.style(*DANGER_COLOR);
+        let repository = &value.repository_nickname.style(*DANGER_COLOR);
+        let status = "BLOCKED".style(*DANGER_COLOR).to_string();
+        let emdash = EMDASH.style(*DANGER_COLOR);
+        let reason = format!("SECRET DETECTED on line {}: {}", value.line, value.rule)
+            .style(*DANGER_COLOR)
+            .to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {filename:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {reason}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<GenerationFailed> for AppEvent {
+    fn from(value: GenerationFailed) -> Self {
+        let namespace = Uuid::NAMESPACE_OID;
+
+        let simple_urn = format!("{}://{:?}", &value.repository_nickname, value.target_file);
+        let event_id = Uuid::new_v3(&namespace, simple_urn.as_ref()).to_string();
+        // Failures that may pass are retried on their own; the others need the file edited.
+        let color = if value.kind.is_transient() { *ALERT_COLOR } else { *DANGER_COLOR };
+        let time_stamp = "\u{2014}\u{2014}".style(color);
+        let binding = &value.target_file.display();
+        let filename = &binding.style(color);
+        let repository = &value.repository_nickname.style(color);
+        let status = "FAILED".style(color).to_string();
+        let emdash = EMDASH.style(color);
+        let retries = match value.retries {
+            0 => String::new(),
+            1 => " after 1 retry".to_string(),
+            retries => format!(" after {retries} retries"),
+        };
+        let next = if value.kind.is_transient() { "retrying on next poll" } else { "held until edited" };
+        let first_line = value.error.lines().next().unwrap_or_default();
+        let reason = format!("{}{retries}, {next}: {first_line}", value.kind)
+            .style(color)
+            .to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {filename:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {reason}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<RepositoryPaused> for AppEvent {
+    fn from(value: RepositoryPaused) -> Self {
+        let event_id = AppEvent::repository_paused_id(&value.repository_nickname);
+        let time_stamp = "\u{2014}\u{2014}".style(*ALERT_COLOR);
+        let repository = &value.repository_nickname.style(*ALERT_COLOR);
+        let status = "PAUSED".style(*ALERT_COLOR).to_string();
+        let emdash = EMDASH.style(*ALERT_COLOR);
+        let operation = format!("paused: {}", value.operation).style(*ALERT_COLOR).to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {operation:<COLUMN_HEADING_FIVE_LENGTH$}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<PushCompleted> for AppEvent {
+    fn from(value: PushCompleted) -> Self {
+        let event_id = AppEvent::repository_push_id(&value.repository_nickname);
+        let timestamp = &value.when.style(*TIME_COLOR);
+        let repository = &value.repository_nickname.style(*REPO_COLOR);
+        let status = "PUSHED".style(*STATUS).to_string();
+        let emdash = EMDASH.style(*STATUS);
+        let destination = format!("{}/{}", value.remote, value.branch);
+        let plural = if value.commits == 1 { "" } else { "s" };
+        let commits = format!("{} commit{plural}", value.commits).style(*DESCRIPTION_COLOR).to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {timestamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {destination:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {commits}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<PushRejected> for AppEvent {
+    fn from(value: PushRejected) -> Self {
+        let event_id = AppEvent::repository_push_id(&value.repository_nickname);
+        let time_stamp = "\u{2014}\u{2014}".style(*ALERT_COLOR);
+        let repository = &value.repository_nickname.style(*ALERT_COLOR);
+        let status = "UNPUSHED".style(*ALERT_COLOR).to_string();
+        let emdash = EMDASH.style(*ALERT_COLOR);
+        let destination = format!("{}/{}", value.remote, value.branch);
+        let destination = destination.style(*ALERT_COLOR);
+        let reason = value.reason.style(*ALERT_COLOR).to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {destination:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {reason}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<UndoRefused> for AppEvent {
+    fn from(value: UndoRefused) -> Self {
+        let event_id = AppEvent::repository_undo_id(&value.repository_nickname);
+        let time_stamp = "\u{2014}\u{2014}".style(*ALERT_COLOR);
+        let repository = &value.repository_nickname.style(*ALERT_COLOR);
+        let status = "REFUSED".style(*ALERT_COLOR).to_string();
+        let emdash = EMDASH.style(*ALERT_COLOR);
+        let subject = "undo".style(*ALERT_COLOR);
+        let reason = value.reason.style(*ALERT_COLOR).to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {subject:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {reason}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}
+
+impl From<ShadowHistoryArchived> for AppEvent {
+    fn from(value: ShadowHistoryArchived) -> Self {
+        let event_id = AppEvent::repository_shadow_id(&value.repository_nickname);
+        let time_stamp = "\u{2014}\u{2014}".style(*ALERT_COLOR);
+        let repository = &value.repository_nickname.style(*ALERT_COLOR);
+        let status = "ARCHIVED".style(*ALERT_COLOR).to_string();
+        let emdash = EMDASH.style(*ALERT_COLOR);
+        let branch = &value.branch.style(*ALERT_COLOR);
+        let note = format!("moved on; shadow history kept at {}", value.archive_ref)
+            .style(*ALERT_COLOR)
+            .to_string();
+        let halftab = &HALFTAB.clone();
+        let display_string = format!(
+            "\
+                            {halftab}\
+                            {repository:<COLUMN_HEADING_ONE_LENGTH$} \
+                            {time_stamp:^COLUMN_HEADING_TWO_LENGTH$} \
+                            {status:^COLUMN_HEADING_THREE_LENGTH$} \
+                            {emdash:^COLUMN_HEADING_FOUR_LENGTH$} \
+                            {branch:<COLUMN_HEADING_FIVE_LENGTH$} \
+                            {note}"
+        );
+        AppEvent::new(event_id, display_string)
+    }
+}